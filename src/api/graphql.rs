@@ -0,0 +1,505 @@
+use std::sync::Arc;
+
+use async_graphql::{
+    http::GraphiQLSource, ComplexObject, Context, EmptyMutation, EmptySubscription, Enum,
+    InputObject, InputValueError, InputValueResult, Object, Result, Scalar, ScalarType, Schema,
+    SimpleObject, Value,
+};
+use axum::{
+    extract::State,
+    response::{Html, IntoResponse},
+    Json,
+};
+
+use crate::db::{
+    model::{Account, Chart, Lifecycle, PoolInfo, Resolution, Token, Trade, TradeType},
+    Database,
+};
+
+use super::{
+    rest::{DEFAULT_LIMIT, MAX_LIMIT},
+    AppState,
+};
+
+const MAX_DEPTH: usize = 8;
+/// 쿼리 하나가 쓸 수 있는 비용. 목록 필드는 개수만큼, 레코드 전체를 읽는 필드는
+/// `RECORD_LOAD_COST`만큼 더해집니다.
+const MAX_COMPLEXITY: usize = 2_000;
+/// 코인의 거래·차트 레코드 전체를 읽는 필드의 비용
+const RECORD_LOAD_COST: usize = 50;
+
+pub type GmiSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+pub fn schema(db: Arc<Database>) -> GmiSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(db)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+pub async fn graphql_handler(
+    State(state): State<AppState>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    Json(state.schema.execute(request).await)
+}
+
+pub async fn graphiql() -> impl IntoResponse {
+    Html(GraphiQLSource::build().endpoint("/graphql").finish())
+}
+
+fn page_bounds(offset: Option<u32>, limit: Option<u32>) -> (usize, usize) {
+    let offset = offset.unwrap_or(0) as usize;
+    let limit = limit
+        .map(|limit| limit as usize)
+        .unwrap_or(DEFAULT_LIMIT)
+        .min(MAX_LIMIT);
+    (offset, limit)
+}
+
+/// GraphQL `Int`는 32비트라 u64 값은 10진수 문자열로 주고받습니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct U64(pub u64);
+
+impl From<u64> for U64 {
+    fn from(value: u64) -> Self {
+        U64(value)
+    }
+}
+
+#[Scalar]
+impl ScalarType for U64 {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match &value {
+            Value::String(text) => Ok(U64(text.parse()?)),
+            Value::Number(number) => match number.as_u64() {
+                Some(number) => Ok(U64(number)),
+                None => Err(InputValueError::expected_type(value)),
+            },
+            _ => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.0.to_string())
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn token(&self, ctx: &Context<'_>, coin_type: String) -> Result<Option<TokenObject>> {
+        let db = ctx.data::<Arc<Database>>()?;
+        Ok(db.get_token(&coin_type).await?.map(TokenObject::from))
+    }
+
+    #[graphql(complexity = "page_bounds(offset, limit).1 * child_complexity")]
+    async fn tokens(
+        &self,
+        ctx: &Context<'_>,
+        offset: Option<u32>,
+        limit: Option<u32>,
+        search: Option<String>,
+    ) -> Result<TokenPage> {
+        let db = ctx.data::<Arc<Database>>()?;
+        let (offset, limit) = page_bounds(offset, limit);
+        let tokens = match search {
+            Some(search) => db.search_tokens(&search, offset, limit).await?,
+            None => db.get_tokens(offset, limit).await?,
+        };
+        Ok(TokenPage {
+            items: tokens.into_iter().map(TokenObject::from).collect(),
+            offset: offset as u32,
+            limit: limit as u32,
+        })
+    }
+
+    async fn pool(&self, ctx: &Context<'_>, coin_type: String) -> Result<Option<PoolInfoObject>> {
+        let db = ctx.data::<Arc<Database>>()?;
+        Ok(db
+            .get_pool_info(&coin_type)
+            .await?
+            .map(PoolInfoObject::from))
+    }
+
+    #[graphql(complexity = "RECORD_LOAD_COST + child_complexity")]
+    async fn trades(
+        &self,
+        ctx: &Context<'_>,
+        coin_type: String,
+        offset: Option<u32>,
+        limit: Option<u32>,
+        filter: Option<TradeFilter>,
+    ) -> Result<TradePage> {
+        trade_page(ctx, &coin_type, offset, limit, filter).await
+    }
+
+    #[graphql(complexity = "RECORD_LOAD_COST + child_complexity")]
+    async fn charts(
+        &self,
+        ctx: &Context<'_>,
+        coin_type: String,
+        resolution: Option<ChartResolution>,
+        filter: Option<ChartFilter>,
+    ) -> Result<Vec<ChartObject>> {
+        charts(ctx, &coin_type, resolution, filter).await
+    }
+
+    async fn account(&self, ctx: &Context<'_>, account: String) -> Result<Option<AccountObject>> {
+        let db = ctx.data::<Arc<Database>>()?;
+        Ok(db.get_account(&account).await?.map(AccountObject::from))
+    }
+}
+
+async fn trade_page(
+    ctx: &Context<'_>,
+    coin_type: &str,
+    offset: Option<u32>,
+    limit: Option<u32>,
+    filter: Option<TradeFilter>,
+) -> Result<TradePage> {
+    let db = ctx.data::<Arc<Database>>()?;
+    let (offset, limit) = page_bounds(offset, limit);
+    let filter = filter.unwrap_or_default();
+    let trades: Vec<TradeObject> = db
+        .get_trade_data(coin_type)
+        .await?
+        .map(|trade_data| trade_data.trades)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|trade| filter.matches(trade))
+        .map(TradeObject::from)
+        .collect();
+
+    Ok(TradePage {
+        total: trades.len() as u32,
+        items: trades.into_iter().skip(offset).take(limit).collect(),
+        offset: offset as u32,
+        limit: limit as u32,
+    })
+}
+
+async fn charts(
+    ctx: &Context<'_>,
+    coin_type: &str,
+    resolution: Option<ChartResolution>,
+    filter: Option<ChartFilter>,
+) -> Result<Vec<ChartObject>> {
+    let db = ctx.data::<Arc<Database>>()?;
+    let resolution = resolution.unwrap_or(ChartResolution::FiveMinutes);
+    let filter = filter.unwrap_or_default();
+    let charts = db
+        .get_chart_data(coin_type)
+        .await?
+        .map(|chart_data| chart_data.candles(resolution.into()))
+        .transpose()?
+        .unwrap_or_default();
+
+    Ok(charts
+        .into_iter()
+        .filter(|chart| filter.matches(chart))
+        .take(filter.limit.map_or(MAX_LIMIT, |limit| limit as usize))
+        .map(ChartObject::from)
+        .collect())
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "Token", complex)]
+pub struct TokenObject {
+    coin_type: String,
+    name: String,
+    symbol: String,
+    decimals: u8,
+    icon_url: Option<String>,
+    description: String,
+    total_supply: U64,
+    create_time: U64,
+    recent_trade: Option<U64>,
+    create_digest: String,
+    package_version: Option<U64>,
+    lifecycle: LifecycleObject,
+}
+
+impl From<Token> for TokenObject {
+    fn from(token: Token) -> Self {
+        TokenObject {
+            coin_type: token.coin_type,
+            name: token.name,
+            symbol: token.symbol,
+            decimals: token.decimals,
+            icon_url: token.icon_url,
+            description: token.description,
+            total_supply: token.total_supply.into(),
+            create_time: token.create_time.into(),
+            recent_trade: token.recent_trade.map(U64),
+            create_digest: token.create_digest,
+            package_version: token.package_version.map(U64),
+            lifecycle: token.lifecycle.into(),
+        }
+    }
+}
+
+#[ComplexObject]
+impl TokenObject {
+    async fn pool(&self, ctx: &Context<'_>) -> Result<Option<PoolInfoObject>> {
+        let db = ctx.data::<Arc<Database>>()?;
+        Ok(db
+            .get_pool_info(&self.coin_type)
+            .await?
+            .map(PoolInfoObject::from))
+    }
+
+    #[graphql(complexity = "RECORD_LOAD_COST + child_complexity")]
+    async fn trades(
+        &self,
+        ctx: &Context<'_>,
+        offset: Option<u32>,
+        limit: Option<u32>,
+        filter: Option<TradeFilter>,
+    ) -> Result<TradePage> {
+        trade_page(ctx, &self.coin_type, offset, limit, filter).await
+    }
+
+    #[graphql(complexity = "RECORD_LOAD_COST + child_complexity")]
+    async fn charts(
+        &self,
+        ctx: &Context<'_>,
+        resolution: Option<ChartResolution>,
+        filter: Option<ChartFilter>,
+    ) -> Result<Vec<ChartObject>> {
+        charts(ctx, &self.coin_type, resolution, filter).await
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct TokenPage {
+    items: Vec<TokenObject>,
+    offset: u32,
+    limit: u32,
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "PoolInfo")]
+pub struct PoolInfoObject {
+    coin_type: String,
+    pool_id: String,
+    reserve_meme: U64,
+    reserve_sui: U64,
+    time_stamp: U64,
+    package_version: Option<U64>,
+    lifecycle: LifecycleObject,
+}
+
+impl From<PoolInfo> for PoolInfoObject {
+    fn from(pool_info: PoolInfo) -> Self {
+        PoolInfoObject {
+            coin_type: pool_info.coin_type,
+            pool_id: pool_info.pool_id,
+            reserve_meme: pool_info.reserve_meme.into(),
+            reserve_sui: pool_info.reserve_sui.into(),
+            time_stamp: pool_info.time_stamp.into(),
+            package_version: pool_info.package_version.map(U64),
+            lifecycle: pool_info.lifecycle.into(),
+        }
+    }
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+pub enum LifecycleState {
+    Launching,
+    Graduated,
+    Migrated,
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "Lifecycle")]
+pub struct LifecycleObject {
+    state: LifecycleState,
+    /// 상태가 바뀐 시각
+    changed_at: Option<U64>,
+    venue: Option<String>,
+    target_pool_id: Option<String>,
+}
+
+impl From<Lifecycle> for LifecycleObject {
+    fn from(lifecycle: Lifecycle) -> Self {
+        match lifecycle {
+            Lifecycle::Launching => LifecycleObject {
+                state: LifecycleState::Launching,
+                changed_at: None,
+                venue: None,
+                target_pool_id: None,
+            },
+            Lifecycle::Graduated { graduated_at } => LifecycleObject {
+                state: LifecycleState::Graduated,
+                changed_at: Some(graduated_at.into()),
+                venue: None,
+                target_pool_id: None,
+            },
+            Lifecycle::Migrated {
+                venue,
+                target_pool_id,
+                migrated_at,
+            } => LifecycleObject {
+                state: LifecycleState::Migrated,
+                changed_at: Some(migrated_at.into()),
+                venue: Some(venue),
+                target_pool_id: Some(target_pool_id),
+            },
+        }
+    }
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+pub enum TradeKind {
+    Buy,
+    Sell,
+}
+
+impl From<TradeType> for TradeKind {
+    fn from(trade_type: TradeType) -> Self {
+        match trade_type {
+            TradeType::Buy => TradeKind::Buy,
+            TradeType::Sell => TradeKind::Sell,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "Trade")]
+pub struct TradeObject {
+    account: String,
+    trade_type: TradeKind,
+    sui_amount: U64,
+    timestamp: U64,
+    transaction_hash: String,
+    package_version: Option<U64>,
+    account_meme_balance: Option<U64>,
+    venue: Option<String>,
+}
+
+impl From<Trade> for TradeObject {
+    fn from(trade: Trade) -> Self {
+        TradeObject {
+            account: trade.account,
+            trade_type: trade.trade_type.into(),
+            sui_amount: trade.sui_amount.into(),
+            timestamp: trade.timestamp.into(),
+            transaction_hash: trade.transaction_hash,
+            package_version: trade.package_version.map(U64),
+            account_meme_balance: trade.account_meme_balance.map(U64),
+            venue: trade.venue,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct TradePage {
+    items: Vec<TradeObject>,
+    total: u32,
+    offset: u32,
+    limit: u32,
+}
+
+#[derive(InputObject, Default)]
+pub struct TradeFilter {
+    trade_type: Option<TradeKind>,
+    account: Option<String>,
+    from: Option<U64>,
+    to: Option<U64>,
+}
+
+impl TradeFilter {
+    fn matches(&self, trade: &Trade) -> bool {
+        let trade_type = TradeKind::from(trade.trade_type.clone());
+        self.trade_type.map_or(true, |kind| kind == trade_type)
+            && self
+                .account
+                .as_ref()
+                .map_or(true, |account| account == &trade.account)
+            && self.from.map_or(true, |from| trade.timestamp >= from.0)
+            && self.to.map_or(true, |to| trade.timestamp <= to.0)
+    }
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+pub enum ChartResolution {
+    FiveMinutes,
+    FifteenMinutes,
+    OneHour,
+    FourHours,
+    OneDay,
+}
+
+impl From<ChartResolution> for Resolution {
+    fn from(resolution: ChartResolution) -> Self {
+        match resolution {
+            ChartResolution::FiveMinutes => Resolution::FiveMinutes,
+            ChartResolution::FifteenMinutes => Resolution::FifteenMinutes,
+            ChartResolution::OneHour => Resolution::OneHour,
+            ChartResolution::FourHours => Resolution::FourHours,
+            ChartResolution::OneDay => Resolution::OneDay,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "Chart")]
+pub struct ChartObject {
+    timestamp: U64,
+    open_price: String,
+    high_price: String,
+    low_price: String,
+    close_price: String,
+    current_price: String,
+    liquidity_changes: u32,
+}
+
+impl From<Chart> for ChartObject {
+    fn from(chart: Chart) -> Self {
+        ChartObject {
+            timestamp: chart.chart_timestamp.into(),
+            open_price: chart.open_price,
+            high_price: chart.high_price,
+            low_price: chart.low_price,
+            close_price: chart.close_price,
+            current_price: chart.current_price,
+            liquidity_changes: chart.liquidity_changes,
+        }
+    }
+}
+
+#[derive(InputObject, Default)]
+pub struct ChartFilter {
+    from: Option<U64>,
+    to: Option<U64>,
+    limit: Option<u32>,
+}
+
+impl ChartFilter {
+    fn matches(&self, chart: &Chart) -> bool {
+        self.from
+            .map_or(true, |from| chart.chart_timestamp >= from.0)
+            && self.to.map_or(true, |to| chart.chart_timestamp <= to.0)
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "Account")]
+pub struct AccountObject {
+    account: String,
+    nickname: String,
+    image_url: String,
+}
+
+impl From<Account> for AccountObject {
+    fn from(account: Account) -> Self {
+        AccountObject {
+            account: account.account,
+            nickname: account.nickname,
+            image_url: account.image_url,
+        }
+    }
+}
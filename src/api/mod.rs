@@ -0,0 +1,129 @@
+pub mod graphql;
+pub mod ops;
+pub mod rest;
+pub mod sse;
+pub mod ws;
+
+use std::sync::Arc;
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde_json::json;
+use tokio::sync::broadcast::Sender;
+use tracing::{error, info};
+
+use crate::{
+    config::ApiConfig,
+    db::{model::MarketUpdate, Database},
+    shutdown::{self, Shutdown},
+    supervisor::ComponentStates,
+};
+
+#[derive(Clone)]
+pub struct AppState {
+    pub db: Arc<Database>,
+    /// 실시간 업데이트. 같은 프로세스에서 observer가 돌지 않으면(`api` 모드) 보낼 곳이 없으므로
+    /// `None`이고, `/ws`는 503으로 거절합니다.
+    pub updates: Option<Sender<MarketUpdate>>,
+    pub schema: graphql::GmiSchema,
+    pub components: ComponentStates,
+}
+
+pub fn router(state: AppState, graphiql: bool) -> Router {
+    let graphql = if graphiql {
+        get(graphql::graphiql).post(graphql::graphql_handler)
+    } else {
+        post(graphql::graphql_handler)
+    };
+    Router::new()
+        .route("/tokens", get(rest::list_tokens))
+        .route("/tokens/:coin_type", get(rest::get_token))
+        .route("/pools/:coin_type", get(rest::get_pool_info))
+        .route("/trades/:coin_type", get(rest::list_trades))
+        .route("/charts/:coin_type", get(rest::list_charts))
+        .route("/liquidity/:account", get(rest::list_liquidity))
+        .route("/launches", get(sse::launches))
+        .route("/ws", get(ws::subscribe))
+        .route("/status", get(rest::status))
+        .route("/graphql", graphql)
+        .with_state(state)
+}
+
+pub async fn serve(
+    config: ApiConfig,
+    db: Arc<Database>,
+    updates: Option<Sender<MarketUpdate>>,
+    components: ComponentStates,
+    mut shutdown: Shutdown,
+) -> anyhow::Result<()> {
+    info!(addr = %config.addr, "API server listening");
+    let schema = graphql::schema(db.clone());
+    axum::Server::bind(&config.addr)
+        .serve(
+            router(
+                AppState {
+                    db,
+                    updates,
+                    schema,
+                    components,
+                },
+                config.graphiql,
+            )
+            .into_make_service(),
+        )
+        .with_graceful_shutdown(async move { shutdown::requested(&mut shutdown).await })
+        .await?;
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum ApiError {
+    NotFound(String),
+    BadRequest(String),
+    Unavailable(String),
+    Internal(String),
+}
+
+impl ApiError {
+    /// 실시간 업데이트를 만드는 observer가 없는 프로세스에서 스트리밍 요청을 거절합니다.
+    pub fn live_updates_unavailable() -> Self {
+        ApiError::Unavailable(
+            "Live updates are served only by processes running the observer (mode `all`)"
+                .to_string(),
+        )
+    }
+}
+
+impl From<surrealdb::Error> for ApiError {
+    fn from(err: surrealdb::Error) -> Self {
+        ApiError::Internal(err.to_string())
+    }
+}
+
+impl From<crate::error::Error> for ApiError {
+    fn from(err: crate::error::Error) -> Self {
+        ApiError::Internal(err.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            ApiError::NotFound(message) => (StatusCode::NOT_FOUND, message),
+            ApiError::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            ApiError::Unavailable(message) => (StatusCode::SERVICE_UNAVAILABLE, message),
+            ApiError::Internal(message) => {
+                error!(error = %message, "API error");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Internal server error".to_string(),
+                )
+            }
+        };
+        (status, Json(json!({ "error": message }))).into_response()
+    }
+}
@@ -0,0 +1,213 @@
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde_json::{json, Value};
+use sui_sdk::{rpc_types::EventFilter, SuiClient};
+use tokio::sync::OnceCell;
+use tracing::{error, info};
+
+use crate::{
+    db::Database,
+    metrics,
+    observe::{resolve_replay_filter, EventChannel, EventSource},
+    shutdown::{self, Shutdown},
+    sui::SuiClientPool,
+    supervisor::{ComponentState, ComponentStates},
+};
+
+const DB_PING_TIMEOUT: Duration = Duration::from_secs(2);
+const LATEST_EVENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone)]
+pub struct OpsState {
+    pub db: Arc<Database>,
+    /// observer 모드에서만 있습니다. 없으면 RPC와 처리 대기열은 검사하지 않습니다.
+    pub sui: Option<Arc<SuiClientPool>>,
+    pub components: ComponentStates,
+    /// 구독과 이벤트 처리 사이 채널(채널마다)의 크기
+    pub event_capacity: usize,
+    /// observer 모드에서만 있습니다.
+    pub lag: Option<LagCheck>,
+}
+
+/// 마지막으로 처리한 AMM 이벤트가 체인의 최신 AMM 이벤트보다 얼마나 뒤처졌는지 검사합니다.
+#[derive(Clone)]
+pub struct LagCheck {
+    /// 최신 이벤트를 조회할 AMM 패키지. 모든 버전의 이벤트 타입은 원래 패키지 주소를 쓰므로 하나면 됩니다.
+    source: EventSource,
+    max_lag: Duration,
+    /// 시작한 뒤 처리한 이벤트가 없으면 이 시각부터 잽니다.
+    started_at_ms: u64,
+    filter: Arc<OnceCell<EventFilter>>,
+}
+
+impl LagCheck {
+    pub fn new(source: EventSource, max_lag: Duration, started_at_ms: u64) -> Self {
+        LagCheck {
+            source,
+            max_lag,
+            started_at_ms,
+            filter: Arc::new(OnceCell::new()),
+        }
+    }
+
+    async fn latest_event_ms(&self, sui: &SuiClient) -> anyhow::Result<Option<u64>> {
+        let filter = self
+            .filter
+            .get_or_try_init(|| resolve_replay_filter(sui, &self.source))
+            .await?
+            .clone();
+        let page = metrics::rpc(
+            "query_events",
+            sui.event_api().query_events(filter, None, Some(1), true),
+        )
+        .await?;
+        Ok(page.data.first().and_then(|event| event.timestamp_ms))
+    }
+}
+
+pub fn router(state: OpsState) -> Router {
+    Router::new()
+        .route("/metrics", get(prometheus_metrics))
+        .route("/livez", get(livez))
+        .route("/readyz", get(readyz))
+        .with_state(state)
+}
+
+/// 메트릭과 헬스체크 엔드포인트를 제공합니다. API와 별도 포트에서 모든 실행 모드에 열립니다.
+pub async fn serve(
+    addr: SocketAddr,
+    state: OpsState,
+    mut shutdown: Shutdown,
+) -> anyhow::Result<()> {
+    info!(addr = %addr, "Ops server listening");
+    axum::Server::bind(&addr)
+        .serve(router(state).into_make_service())
+        .with_graceful_shutdown(async move { shutdown::requested(&mut shutdown).await })
+        .await?;
+    Ok(())
+}
+
+async fn prometheus_metrics() -> Response {
+    match metrics::encode() {
+        Ok(body) => ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body).into_response(),
+        Err(e) => {
+            error!(error = ?e, "Failed to encode metrics");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// 프로세스가 멈췄는지 확인합니다. 재시작을 포기한 컴포넌트가 있으면 실패합니다.
+///
+/// 체인이 조용하거나 처리가 밀린 것은 재시작으로 나아지지 않으므로 여기서는 보지 않습니다.
+async fn livez(State(state): State<OpsState>) -> Response {
+    let failed: Vec<String> = state
+        .components
+        .snapshot()
+        .into_iter()
+        .filter(|(_, state)| matches!(state, ComponentState::Failed { .. }))
+        .map(|(name, _)| name)
+        .collect();
+    health_response(vec![(
+        "components",
+        failed.is_empty(),
+        json!({ "failed": failed }),
+    )])
+}
+
+/// 요청을 처리할 수 있는지 확인합니다. DB, RPC 연결과 처리 대기열, 처리 지연을 검사합니다.
+async fn readyz(State(state): State<OpsState>) -> Response {
+    let db = match tokio::time::timeout(DB_PING_TIMEOUT, state.db.ping()).await {
+        Ok(Ok(())) => ("db", true, Value::Null),
+        Ok(Err(e)) => ("db", false, json!({ "error": e.to_string() })),
+        Err(_) => ("db", false, json!({ "error": "timed out" })),
+    };
+    let mut checks = vec![db];
+    if let Some(sui) = &state.sui {
+        checks.push((
+            "rpc",
+            sui.is_healthy(),
+            json!({ "endpoint": sui.current_endpoint().http_url }),
+        ));
+        checks.push(backlog_check(state.event_capacity));
+        if let Some(lag) = &state.lag {
+            checks.push(lag_check(&sui.client(), lag).await);
+        }
+    }
+    health_response(checks)
+}
+
+/// 채널이 가득 차 구독이 처리를 기다리고 있으면 실패합니다.
+///
+/// 체인 지연은 이벤트가 없는 동안에도 늘어나므로 판단에 쓰지 않고 참고로만 보여 줍니다.
+fn backlog_check(event_capacity: usize) -> (&'static str, bool, Value) {
+    let mut full = false;
+    let mut depths = serde_json::Map::new();
+    for channel in EventChannel::ALL {
+        let depth = metrics::EVENT_CHANNEL_DEPTH
+            .with_label_values(&[channel.name()])
+            .get();
+        full |= depth >= event_capacity as i64;
+        depths.insert(channel.name().to_string(), json!(depth));
+    }
+    (
+        "backlog",
+        !full,
+        json!({
+            "depth": depths,
+            "capacity": event_capacity,
+            "chainLagMs": metrics::chain_lag_ms(),
+        }),
+    )
+}
+
+/// 체인의 최신 AMM 이벤트 시각과 마지막으로 처리한 이벤트 시각의 차이가 `max_lag`를 넘으면 실패합니다.
+///
+/// 체크포인트가 아닌 최신 이벤트와 비교하므로 체인이 조용한 동안에는 지연이 늘지 않습니다.
+async fn lag_check(sui: &SuiClient, lag: &LagCheck) -> (&'static str, bool, Value) {
+    let latest = match tokio::time::timeout(LATEST_EVENT_TIMEOUT, lag.latest_event_ms(sui)).await {
+        Ok(Ok(latest)) => latest,
+        Ok(Err(e)) => return ("lag", false, json!({ "error": e.to_string() })),
+        Err(_) => return ("lag", false, json!({ "error": "timed out" })),
+    };
+    let processed = metrics::LAST_EVENT_TIMESTAMP.get().max(0) as u64;
+    let lag_ms = latest.map_or(0, |latest| {
+        latest.saturating_sub(processed.max(lag.started_at_ms))
+    });
+    let max_lag_ms = lag.max_lag.as_millis() as u64;
+    (
+        "lag",
+        lag_ms <= max_lag_ms,
+        json!({
+            "lagMs": lag_ms,
+            "maxLagMs": max_lag_ms,
+            "latestEventMs": latest,
+            "lastProcessedMs": (processed > 0).then_some(processed),
+        }),
+    )
+}
+
+fn health_response(checks: Vec<(&'static str, bool, Value)>) -> Response {
+    let healthy = checks.iter().all(|(_, ok, _)| *ok);
+    let checks: serde_json::Map<String, Value> = checks
+        .into_iter()
+        .map(|(name, ok, detail)| (name.to_string(), json!({ "ok": ok, "detail": detail })))
+        .collect();
+    let status = if healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    let body = json!({
+        "status": if healthy { "ok" } else { "unavailable" },
+        "checks": checks,
+    });
+    (status, Json(body)).into_response()
+}
@@ -0,0 +1,156 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    db::model::{Chart, LiquidityAction, PoolInfo, Resolution, Token, Trade},
+    supervisor::ComponentState,
+};
+
+use super::{ApiError, AppState};
+
+pub(crate) const DEFAULT_LIMIT: usize = 50;
+pub(crate) const MAX_LIMIT: usize = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct PageQuery {
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+impl PageQuery {
+    fn offset(&self) -> usize {
+        self.offset.unwrap_or(0)
+    }
+
+    fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChartQuery {
+    pub resolution: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Candles {
+    pub coin_type: String,
+    pub resolution: Resolution,
+    pub charts: Vec<Chart>,
+}
+
+pub async fn list_tokens(
+    State(state): State<AppState>,
+    Query(page): Query<PageQuery>,
+) -> Result<Json<Page<Token>>, ApiError> {
+    let (offset, limit) = (page.offset(), page.limit());
+    let items = state.db.get_tokens(offset, limit).await?;
+    let total = state.db.count_tokens().await?;
+    Ok(Json(Page {
+        items,
+        total,
+        offset,
+        limit,
+    }))
+}
+
+pub async fn get_token(
+    State(state): State<AppState>,
+    Path(coin_type): Path<String>,
+) -> Result<Json<Token>, ApiError> {
+    state
+        .db
+        .get_token(&coin_type)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("Token not found: {}", coin_type)))
+}
+
+pub async fn get_pool_info(
+    State(state): State<AppState>,
+    Path(coin_type): Path<String>,
+) -> Result<Json<PoolInfo>, ApiError> {
+    state
+        .db
+        .get_pool_info(&coin_type)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("Pool not found: {}", coin_type)))
+}
+
+pub async fn list_trades(
+    State(state): State<AppState>,
+    Path(coin_type): Path<String>,
+    Query(page): Query<PageQuery>,
+) -> Result<Json<Page<Trade>>, ApiError> {
+    let (offset, limit) = (page.offset(), page.limit());
+    let (items, total) = state.db.get_trades(&coin_type, offset, limit).await?;
+    Ok(Json(Page {
+        items,
+        total,
+        offset,
+        limit,
+    }))
+}
+
+/// 계정의 유동성 공급/회수 기록
+pub async fn list_liquidity(
+    State(state): State<AppState>,
+    Path(account): Path<String>,
+    Query(page): Query<PageQuery>,
+) -> Result<Json<Page<LiquidityAction>>, ApiError> {
+    let (offset, limit) = (page.offset(), page.limit());
+    let items = state
+        .db
+        .get_liquidity_actions(&account, offset, limit)
+        .await?;
+    let total = state.db.count_liquidity_actions(&account).await?;
+    Ok(Json(Page {
+        items,
+        total,
+        offset,
+        limit,
+    }))
+}
+
+pub async fn list_charts(
+    State(state): State<AppState>,
+    Path(coin_type): Path<String>,
+    Query(query): Query<ChartQuery>,
+) -> Result<Json<Candles>, ApiError> {
+    let resolution = match query.resolution {
+        Some(resolution) => Resolution::from_str(&resolution).map_err(ApiError::BadRequest)?,
+        None => Resolution::FiveMinutes,
+    };
+    let charts = state
+        .db
+        .get_chart_data(&coin_type)
+        .await?
+        .map(|chart_data| chart_data.candles(resolution))
+        .transpose()?
+        .unwrap_or_default();
+
+    Ok(Json(Candles {
+        coin_type,
+        resolution,
+        charts,
+    }))
+}
+
+/// 파이프라인 컴포넌트별 상태
+pub async fn status(State(state): State<AppState>) -> Json<BTreeMap<String, ComponentState>> {
+    Json(state.components.snapshot())
+}
@@ -0,0 +1,77 @@
+use std::{collections::HashSet, convert::Infallible};
+
+use axum::{
+    extract::{Query, State},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use serde::{Deserialize, Serialize};
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
+
+use crate::db::model::{MarketUpdate, PoolInfo, Token};
+
+use super::{ApiError, AppState};
+
+const DEFAULT_REPLAY: usize = 10;
+const MAX_REPLAY: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct LaunchQuery {
+    pub replay: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct Launch {
+    token: Token,
+    #[serde(rename = "poolInfo")]
+    pool_info: Option<PoolInfo>,
+}
+
+/// 새로 생성된 풀을 SSE로 전송합니다. 연결 시 최근 `replay`개의 런칭을 먼저 보냅니다.
+///
+/// 런칭은 같은 프로세스의 observer가 보내므로 `api` 모드에서는 503을 반환합니다.
+pub async fn launches(
+    State(state): State<AppState>,
+    Query(query): Query<LaunchQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    // 재전송 조회 중 저장되는 런칭을 놓치지 않도록 먼저 구독합니다.
+    let updates = state
+        .updates
+        .as_ref()
+        .ok_or_else(ApiError::live_updates_unavailable)?
+        .subscribe();
+    let updates = BroadcastStream::new(updates);
+
+    let replay = query.replay.unwrap_or(DEFAULT_REPLAY).min(MAX_REPLAY);
+    let mut launches = Vec::new();
+    for token in state.db.get_tokens(0, replay).await?.into_iter().rev() {
+        let pool_info = state.db.get_pool_info(&token.coin_type).await?;
+        launches.push(Launch { token, pool_info });
+    }
+    let replayed: HashSet<String> = launches
+        .iter()
+        .map(|launch| launch.token.coin_type.clone())
+        .collect();
+
+    let replay_stream = tokio_stream::iter(launches).map(|launch| Ok(launch_event(&launch)));
+    let live_stream = updates.filter_map(move |update| match update {
+        Ok(MarketUpdate::Launch {
+            coin_type,
+            token,
+            pool_info,
+        }) if !replayed.contains(&coin_type) => Some(Ok(launch_event(&Launch {
+            token,
+            pool_info: Some(pool_info),
+        }))),
+        _ => None,
+    });
+
+    Ok(Sse::new(replay_stream.chain(live_stream)).keep_alive(KeepAlive::default()))
+}
+
+fn launch_event(launch: &Launch) -> Event {
+    Event::default()
+        .event("launch")
+        .id(launch.token.coin_type.clone())
+        .json_data(launch)
+        .unwrap_or_else(|e| Event::default().event("error").data(e.to_string()))
+}
@@ -0,0 +1,140 @@
+use std::{collections::HashSet, sync::Arc};
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    response::Response,
+};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+use tracing::info;
+
+use crate::{
+    db::{model::MarketUpdate, Database},
+    metrics,
+};
+
+use super::{ApiError, AppState};
+
+/// 클라이언트 → 서버 메시지
+/// `{"op":"subscribe","coinType":"0x..::meme::MEME"}`
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op")]
+enum ClientMessage {
+    #[serde(rename = "subscribe")]
+    Subscribe {
+        #[serde(rename = "coinType")]
+        coin_type: String,
+    },
+    #[serde(rename = "unsubscribe")]
+    Unsubscribe {
+        #[serde(rename = "coinType")]
+        coin_type: String,
+    },
+}
+
+pub async fn subscribe(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+) -> Result<Response, ApiError> {
+    let updates = state
+        .updates
+        .as_ref()
+        .ok_or_else(ApiError::live_updates_unavailable)?
+        .subscribe();
+    Ok(ws.on_upgrade(move |socket| handle_socket(socket, updates, state.db)))
+}
+
+async fn handle_socket(
+    mut socket: WebSocket,
+    mut updates: Receiver<MarketUpdate>,
+    db: Arc<Database>,
+) {
+    let mut coin_types: HashSet<String> = HashSet::new();
+
+    loop {
+        tokio::select! {
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let reply = match serde_json::from_str::<ClientMessage>(&text) {
+                        Ok(ClientMessage::Subscribe { coin_type }) => {
+                            let reply = json!({ "type": "subscribed", "coinType": coin_type });
+                            coin_types.insert(coin_type);
+                            reply
+                        }
+                        Ok(ClientMessage::Unsubscribe { coin_type }) => {
+                            coin_types.remove(&coin_type);
+                            json!({ "type": "unsubscribed", "coinType": coin_type })
+                        }
+                        Err(e) => json!({ "type": "error", "error": e.to_string() }),
+                    };
+                    if socket.send(Message::Text(reply.to_string())).await.is_err() {
+                        break;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+            update = updates.recv() => match update {
+                Ok(update) => {
+                    if !coin_types.contains(update.coin_type()) {
+                        continue;
+                    }
+                    let Ok(text) = serde_json::to_string(&update) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(text)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    metrics::LAGGED_UPDATES.inc_by(skipped);
+                    let notice = json!({ "type": "lagged", "skipped": skipped });
+                    if socket.send(Message::Text(notice.to_string())).await.is_err() {
+                        break;
+                    }
+                    // 놓친 업데이트 대신 구독 중인 코인의 최신 상태를 다시 보냅니다.
+                    if resync(&mut socket, &db, &coin_types).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Closed) => break,
+            },
+        }
+    }
+
+    info!("WebSocket client disconnected");
+}
+
+async fn resync(
+    socket: &mut WebSocket,
+    db: &Database,
+    coin_types: &HashSet<String>,
+) -> Result<(), axum::Error> {
+    for coin_type in coin_types {
+        let mut snapshot = Vec::new();
+        if let Ok(Some(pool_info)) = db.get_pool_info(coin_type).await {
+            snapshot.push(MarketUpdate::PoolInfo {
+                coin_type: coin_type.clone(),
+                pool_info,
+            });
+        }
+        if let Ok(Some(chart_data)) = db.get_chart_data(coin_type).await {
+            if let Some(chart) = chart_data.charts.into_iter().next() {
+                snapshot.push(MarketUpdate::Chart {
+                    coin_type: coin_type.clone(),
+                    chart,
+                });
+            }
+        }
+        for update in snapshot {
+            if let Ok(text) = serde_json::to_string(&update) {
+                socket.send(Message::Text(text)).await?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,1257 @@
+use std::{
+    collections::BTreeMap, fmt, fs, net::SocketAddr, path::Path, str::FromStr, time::Duration,
+};
+
+use reqwest::header::{HeaderName, HeaderValue};
+use serde::Deserialize;
+use sui_sdk::types::{base_types::ObjectID, Identifier};
+use tracing_subscriber::EnvFilter;
+
+use crate::{dex::Venue, rpc_proxy, sui::preset_urls};
+
+/// 설정 파일 경로를 지정하는 환경 변수
+const CONFIG_PATH_ENV: &str = "GMI_CONFIG";
+const DEFAULT_CONFIG_PATH: &str = "config.toml";
+
+const DEFAULT_API_ADDR: &str = "0.0.0.0:8080";
+const DEFAULT_OPS_ADDR: &str = "0.0.0.0:9090";
+const DEFAULT_MAX_LAG_SECS: u64 = 300;
+const DEFAULT_LOG_LEVEL: &str = "info";
+const DEFAULT_SERVICE_NAME: &str = "gmi-server";
+const DEFAULT_WS_PING_INTERVAL_SECS: u64 = 1;
+const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 60;
+const DEFAULT_HEALTH_CHECK_INTERVAL_SECS: u64 = 10;
+const DEFAULT_EVENT_CHANNEL_CAPACITY: usize = 100;
+const DEFAULT_UPDATE_CHANNEL_CAPACITY: usize = 1000;
+const DEFAULT_MAX_RESTARTS: u32 = 5;
+const DEFAULT_INITIAL_BACKOFF_MS: u64 = 500;
+const DEFAULT_MAX_BACKOFF_SECS: u64 = 60;
+const DEFAULT_STABLE_AFTER_SECS: u64 = 300;
+const DEFAULT_RETRY_MAX_ATTEMPTS: u32 = 5;
+const DEFAULT_RETRY_INITIAL_BACKOFF_MS: u64 = 1000;
+const DEFAULT_RETRY_MAX_BACKOFF_SECS: u64 = 60;
+const DEFAULT_RECONCILE_INTERVAL_SECS: u64 = 600;
+const DEFAULT_AMM_MODULE: &str = "amm";
+/// 처리할 수 있는 AMM 이벤트 이름
+///
+/// `DEFAULT_AMM_EVENTS`에 없는 이벤트는 체인의 이벤트로 레이아웃을 확인하지 못했으므로 패키지의
+/// `events`에 적어야 처리합니다.
+pub const AMM_EVENTS: [&str; 6] = [
+    "CreatePoolEvent",
+    "SwapEvent",
+    "AddLiquidityEvent",
+    "RemoveLiquidityEvent",
+    "GraduateEvent",
+    "MigratePoolEvent",
+];
+/// `events`를 적지 않은 패키지가 처리하는 AMM 이벤트 이름
+pub const DEFAULT_AMM_EVENTS: [&str; 2] = ["CreatePoolEvent", "SwapEvent"];
+const NETWORKS: [&str; 5] = ["testnet", "devnet", "mainnet", "localnet", "custom"];
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub network: NetworkConfig,
+    pub package: PackageConfig,
+    pub dex: DexConfig,
+    pub db: DbConfig,
+    pub api: ApiConfig,
+    pub ops: OpsConfig,
+    pub channel: ChannelConfig,
+    pub supervisor: SupervisorConfig,
+    pub retry: RetryConfig,
+    pub reconcile: ReconcileConfig,
+    pub log: LogConfig,
+    /// 설정하지 않으면 trace를 내보내지 않습니다.
+    pub otlp: Option<OtlpConfig>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NetworkConfig {
+    /// testnet | devnet | mainnet | localnet | custom
+    pub build: String,
+    /// 첫 번째가 기본 엔드포인트, 나머지는 장애 시 전환할 예비 엔드포인트
+    pub endpoints: Vec<RpcEndpoint>,
+    pub ws_ping_interval: Duration,
+    pub request_timeout: Duration,
+    pub health_check_interval: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct RpcEndpoint {
+    pub http_url: String,
+    pub ws_url: String,
+    /// 인증이 필요한 RPC 제공자용 (username, password)
+    pub basic_auth: Option<(String, String)>,
+    /// 모든 요청에 붙이는 헤더 (예: API 키). 설정하면 `rpc_proxy`를 거쳐 연결합니다.
+    pub headers: Vec<(HeaderName, HeaderValue)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PackageConfig {
+    /// 배포된 AMM 패키지의 모든 버전
+    pub amm_packages: Vec<AmmPackage>,
+    /// AMM 이벤트를 정의한 Move 모듈 이름
+    pub amm_module: Identifier,
+}
+
+#[derive(Debug, Clone)]
+pub struct AmmPackage {
+    pub package_id: ObjectID,
+    pub version: u64,
+    /// 이 패키지에서 처리할 이벤트 이름
+    pub events: Vec<String>,
+}
+
+impl AmmPackage {
+    pub fn handles(&self, event_name: &str) -> bool {
+        self.events.iter().any(|event| event == event_name)
+    }
+}
+
+impl PackageConfig {
+    /// 이벤트를 발생시킨 패키지 ID로 AMM 패키지를 찾습니다.
+    pub fn find(&self, package_id: &ObjectID) -> Option<&AmmPackage> {
+        self.amm_packages
+            .iter()
+            .find(|package| &package.package_id == package_id)
+    }
+}
+
+/// 졸업한 토큰의 스왑을 수집할 외부 DEX. 패키지 ID를 설정한 DEX만 구독합니다.
+#[derive(Debug, Clone)]
+pub struct DexConfig {
+    pub venues: Vec<DexVenue>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DexVenue {
+    pub venue: Venue,
+    /// 스왑 이벤트를 정의한 (최초 배포) 패키지 ID
+    pub package_id: ObjectID,
+}
+
+#[derive(Debug, Clone)]
+pub struct DbConfig {
+    pub url: String,
+    pub username: String,
+    pub password: String,
+    pub namespace: String,
+    pub name: String,
+    pub client_id: String,
+    pub client_password: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ApiConfig {
+    pub addr: SocketAddr,
+    /// `/graphql`에 GraphiQL 화면을 띄울지. 개발 환경에서만 켭니다.
+    pub graphiql: bool,
+}
+
+/// 메트릭 등 운영용 엔드포인트. 모든 실행 모드에서 열립니다.
+#[derive(Debug, Clone)]
+pub struct OpsConfig {
+    pub addr: SocketAddr,
+    /// 체인의 최신 AMM 이벤트보다 마지막으로 처리한 이벤트가 이만큼 뒤처지면 준비되지 않은 것으로 봅니다.
+    pub max_lag: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChannelConfig {
+    pub event_capacity: usize,
+    pub update_capacity: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct SupervisorConfig {
+    /// 프로세스를 종료하기 전까지 허용하는 연속 재시작 횟수
+    pub max_restarts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// 이 시간 이상 동작한 뒤 실패하면 연속 실패 횟수를 초기화합니다.
+    pub stable_after: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// 이벤트 처리를 시도하는 최대 횟수. 모두 실패하면 dead letter로 저장합니다.
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReconcileConfig {
+    /// 저장된 풀 reserve를 체인의 풀 객체와 비교하는 주기
+    pub interval: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    /// `EnvFilter` 형식의 레벨 지시자 (예: "info,gmi_server=debug")
+    pub level: String,
+    pub format: LogFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err("expected text or json".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OtlpConfig {
+    /// OTLP gRPC 수집기 주소 (예: "http://localhost:4317")
+    pub endpoint: String,
+    pub service_name: String,
+}
+
+/// 검증 중 발견된 모든 설정 오류
+#[derive(Debug)]
+pub struct ConfigError(pub Vec<String>);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid configuration ({} errors)", self.0.len())?;
+        for error in &self.0 {
+            write!(f, "\n  - {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// 설정 파일을 읽고 환경 변수로 덮어쓴 뒤 검증합니다.
+    ///
+    /// 파일 경로는 `GMI_CONFIG`, 없으면 `config.toml`이며 기본 경로의 파일은 없어도 됩니다.
+    pub fn load() -> Result<Self, ConfigError> {
+        let mut raw = match std::env::var(CONFIG_PATH_ENV) {
+            Ok(path) => RawConfig::from_file(&path)?,
+            Err(_) if Path::new(DEFAULT_CONFIG_PATH).exists() => {
+                RawConfig::from_file(DEFAULT_CONFIG_PATH)?
+            }
+            Err(_) => RawConfig::default(),
+        };
+
+        let mut errors = Vec::new();
+        raw.apply_env(&|key| std::env::var(key).ok(), &mut errors);
+        let config = raw.validate(&mut errors);
+
+        match config {
+            Some(config) if errors.is_empty() => Ok(config),
+            _ => Err(ConfigError(errors)),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawConfig {
+    network: RawNetworkConfig,
+    package: RawPackageConfig,
+    dex: RawDexConfig,
+    db: RawDbConfig,
+    api: RawApiConfig,
+    ops: RawOpsConfig,
+    channel: RawChannelConfig,
+    supervisor: RawSupervisorConfig,
+    retry: RawRetryConfig,
+    reconcile: RawReconcileConfig,
+    log: RawLogConfig,
+    otlp: RawOtlpConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawNetworkConfig {
+    build: Option<String>,
+    http_url: Option<String>,
+    ws_url: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    headers: Option<RawHeaders>,
+    fallbacks: Option<RawFallbacks>,
+    ws_ping_interval_secs: Option<u64>,
+    request_timeout_secs: Option<u64>,
+    health_check_interval_secs: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRpcEndpoint {
+    http_url: String,
+    ws_url: String,
+    username: Option<String>,
+    password: Option<String>,
+    headers: Option<RawHeaders>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(transparent)]
+struct RawFallbacks(Vec<RawRpcEndpoint>);
+
+/// 환경 변수 형식: `http_url|ws_url,http_url|ws_url`
+impl FromStr for RawFallbacks {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .map(|entry| match entry.trim().split_once('|') {
+                Some((http_url, ws_url)) => Ok(RawRpcEndpoint {
+                    http_url: http_url.to_string(),
+                    ws_url: ws_url.to_string(),
+                    username: None,
+                    password: None,
+                    headers: None,
+                }),
+                None => Err(format!("expected http_url|ws_url, got {:?}", entry)),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(RawFallbacks)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(transparent)]
+struct RawHeaders(BTreeMap<String, String>);
+
+/// 환경 변수 형식: `name=value,name=value`
+impl FromStr for RawHeaders {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .map(|entry| match entry.split_once('=') {
+                Some((name, value)) => Ok((name.trim().to_string(), value.trim().to_string())),
+                None => Err(format!("expected name=value, got {:?}", entry)),
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()
+            .map(RawHeaders)
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawPackageConfig {
+    amm_package_id: Option<String>,
+    amm_module: Option<String>,
+    amm: Option<Vec<RawAmmPackage>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawAmmPackage {
+    package_id: String,
+    version: u64,
+    events: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawDbConfig {
+    url: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    namespace: Option<String>,
+    name: Option<String>,
+    client_id: Option<String>,
+    client_password: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawApiConfig {
+    addr: Option<String>,
+    graphiql: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawOpsConfig {
+    addr: Option<String>,
+    max_lag_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawChannelConfig {
+    event_capacity: Option<usize>,
+    update_capacity: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawSupervisorConfig {
+    max_restarts: Option<u32>,
+    initial_backoff_ms: Option<u64>,
+    max_backoff_secs: Option<u64>,
+    stable_after_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawRetryConfig {
+    max_attempts: Option<u32>,
+    initial_backoff_ms: Option<u64>,
+    max_backoff_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawReconcileConfig {
+    interval_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawLogConfig {
+    level: Option<String>,
+    format: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawDexConfig {
+    cetus_package_id: Option<String>,
+    turbos_package_id: Option<String>,
+    deepbook_package_id: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawOtlpConfig {
+    endpoint: Option<String>,
+    service_name: Option<String>,
+}
+
+impl RawConfig {
+    fn from_file(path: &str) -> Result<Self, ConfigError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| ConfigError(vec![format!("failed to read {}: {}", path, e)]))?;
+        toml::from_str(&content)
+            .map_err(|e| ConfigError(vec![format!("failed to parse {}: {}", path, e)]))
+    }
+
+    /// 기존 배포와 같은 이름의 환경 변수가 있으면 파일 값을 덮어씁니다.
+    fn apply_env(&mut self, env: EnvLookup, errors: &mut Vec<String>) {
+        env_override(&mut self.network.build, "SUI_RPC", env, errors);
+        env_override(&mut self.network.http_url, "SUI_HTTP_URL", env, errors);
+        env_override(&mut self.network.ws_url, "SUI_WS_URL", env, errors);
+        env_override(&mut self.network.username, "SUI_RPC_USERNAME", env, errors);
+        env_override(&mut self.network.password, "SUI_RPC_PASSWORD", env, errors);
+        env_override(&mut self.network.headers, "SUI_RPC_HEADERS", env, errors);
+        env_override(
+            &mut self.network.fallbacks,
+            "SUI_FALLBACK_URLS",
+            env,
+            errors,
+        );
+        env_override(
+            &mut self.network.ws_ping_interval_secs,
+            "SUI_WS_PING_INTERVAL_SECS",
+            env,
+            errors,
+        );
+        env_override(
+            &mut self.network.request_timeout_secs,
+            "SUI_REQUEST_TIMEOUT_SECS",
+            env,
+            errors,
+        );
+        env_override(
+            &mut self.network.health_check_interval_secs,
+            "SUI_HEALTH_CHECK_INTERVAL_SECS",
+            env,
+            errors,
+        );
+        env_override(
+            &mut self.package.amm_package_id,
+            "AMM_PACKAGE_ID",
+            env,
+            errors,
+        );
+        env_override(&mut self.package.amm_module, "AMM_MODULE", env, errors);
+        env_override(
+            &mut self.dex.cetus_package_id,
+            "CETUS_PACKAGE_ID",
+            env,
+            errors,
+        );
+        env_override(
+            &mut self.dex.turbos_package_id,
+            "TURBOS_PACKAGE_ID",
+            env,
+            errors,
+        );
+        env_override(
+            &mut self.dex.deepbook_package_id,
+            "DEEPBOOK_PACKAGE_ID",
+            env,
+            errors,
+        );
+        env_override(&mut self.db.url, "DB_URL", env, errors);
+        env_override(&mut self.db.username, "DB_USERNAME", env, errors);
+        env_override(&mut self.db.password, "DB_PASSWORD", env, errors);
+        env_override(&mut self.db.namespace, "DB_NAMESPACE", env, errors);
+        env_override(&mut self.db.name, "DB_NAME", env, errors);
+        env_override(&mut self.db.client_id, "DB_CLIENT_ID", env, errors);
+        env_override(
+            &mut self.db.client_password,
+            "DB_CLIENT_PASSWORD",
+            env,
+            errors,
+        );
+        env_override(&mut self.api.addr, "API_ADDR", env, errors);
+        env_override(&mut self.api.graphiql, "API_GRAPHIQL", env, errors);
+        env_override(&mut self.ops.addr, "OPS_ADDR", env, errors);
+        env_override(&mut self.ops.max_lag_secs, "OPS_MAX_LAG_SECS", env, errors);
+        env_override(
+            &mut self.channel.event_capacity,
+            "EVENT_CHANNEL_CAPACITY",
+            env,
+            errors,
+        );
+        env_override(
+            &mut self.channel.update_capacity,
+            "UPDATE_CHANNEL_CAPACITY",
+            env,
+            errors,
+        );
+        env_override(
+            &mut self.supervisor.max_restarts,
+            "SUPERVISOR_MAX_RESTARTS",
+            env,
+            errors,
+        );
+        env_override(
+            &mut self.supervisor.initial_backoff_ms,
+            "SUPERVISOR_INITIAL_BACKOFF_MS",
+            env,
+            errors,
+        );
+        env_override(
+            &mut self.supervisor.max_backoff_secs,
+            "SUPERVISOR_MAX_BACKOFF_SECS",
+            env,
+            errors,
+        );
+        env_override(
+            &mut self.supervisor.stable_after_secs,
+            "SUPERVISOR_STABLE_AFTER_SECS",
+            env,
+            errors,
+        );
+        env_override(
+            &mut self.retry.max_attempts,
+            "RETRY_MAX_ATTEMPTS",
+            env,
+            errors,
+        );
+        env_override(&mut self.log.level, "LOG_LEVEL", env, errors);
+        env_override(&mut self.log.format, "LOG_FORMAT", env, errors);
+        env_override(
+            &mut self.otlp.endpoint,
+            "OTEL_EXPORTER_OTLP_ENDPOINT",
+            env,
+            errors,
+        );
+        env_override(
+            &mut self.otlp.service_name,
+            "OTEL_SERVICE_NAME",
+            env,
+            errors,
+        );
+        env_override(
+            &mut self.retry.initial_backoff_ms,
+            "RETRY_INITIAL_BACKOFF_MS",
+            env,
+            errors,
+        );
+        env_override(
+            &mut self.retry.max_backoff_secs,
+            "RETRY_MAX_BACKOFF_SECS",
+            env,
+            errors,
+        );
+        env_override(
+            &mut self.reconcile.interval_secs,
+            "RECONCILE_INTERVAL_SECS",
+            env,
+            errors,
+        );
+    }
+
+    /// 모든 항목을 검증하고 오류는 `errors`에 모읍니다.
+    fn validate(self, errors: &mut Vec<String>) -> Option<Config> {
+        let build = required(self.network.build, "network.build", "SUI_RPC", errors);
+        if let Some(build) = &build {
+            if !NETWORKS.contains(&build.as_str()) {
+                errors.push(format!(
+                    "network.build: expected one of {:?}, got {:?}",
+                    NETWORKS, build
+                ));
+            }
+        }
+        let preset = build.as_deref().and_then(preset_urls);
+        let http_url = url(
+            self.network.http_url,
+            preset.map(|(http_url, _)| http_url),
+            "network.http_url",
+            &["http://", "https://"],
+            errors,
+        );
+        let ws_url = url(
+            self.network.ws_url,
+            preset.map(|(_, ws_url)| ws_url),
+            "network.ws_url",
+            &["ws://", "wss://"],
+            errors,
+        );
+        let basic_auth = auth_pair(
+            self.network.username,
+            self.network.password,
+            "network",
+            errors,
+        );
+        let headers = headers(self.network.headers, "network.headers", errors);
+        let mut endpoints = Vec::new();
+        if let (Some(http_url), Some(ws_url)) = (http_url, ws_url) {
+            endpoints.push(RpcEndpoint {
+                http_url,
+                ws_url,
+                basic_auth,
+                headers,
+            });
+        }
+        let fallbacks = self.network.fallbacks.map_or_else(Vec::new, |f| f.0);
+        for (index, fallback) in fallbacks.into_iter().enumerate() {
+            let field = format!("network.fallbacks[{}]", index);
+            let http_url = url(
+                Some(fallback.http_url),
+                None,
+                &format!("{}.http_url", field),
+                &["http://", "https://"],
+                errors,
+            );
+            let ws_url = url(
+                Some(fallback.ws_url),
+                None,
+                &format!("{}.ws_url", field),
+                &["ws://", "wss://"],
+                errors,
+            );
+            let basic_auth = auth_pair(fallback.username, fallback.password, &field, errors);
+            let headers = headers(fallback.headers, &format!("{}.headers", field), errors);
+            if let (Some(http_url), Some(ws_url)) = (http_url, ws_url) {
+                endpoints.push(RpcEndpoint {
+                    http_url,
+                    ws_url,
+                    basic_auth,
+                    headers,
+                });
+            }
+        }
+        let health_check_interval_secs = positive(
+            self.network
+                .health_check_interval_secs
+                .unwrap_or(DEFAULT_HEALTH_CHECK_INTERVAL_SECS),
+            "network.health_check_interval_secs",
+            errors,
+        );
+        let request_timeout_secs = positive(
+            self.network
+                .request_timeout_secs
+                .unwrap_or(DEFAULT_REQUEST_TIMEOUT_SECS),
+            "network.request_timeout_secs",
+            errors,
+        );
+        let ws_ping_interval_secs = positive(
+            self.network
+                .ws_ping_interval_secs
+                .unwrap_or(DEFAULT_WS_PING_INTERVAL_SECS),
+            "network.ws_ping_interval_secs",
+            errors,
+        );
+
+        let amm_packages = match (self.package.amm_package_id, self.package.amm) {
+            (Some(_), Some(_)) => {
+                errors.push(
+                    "package.amm_package_id (AMM_PACKAGE_ID) and [[package.amm]] are mutually exclusive"
+                        .to_string(),
+                );
+                None
+            }
+            (Some(id), None) => parse(&id, "package.amm_package_id", errors).map(|package_id| {
+                vec![AmmPackage {
+                    package_id,
+                    version: 1,
+                    events: DEFAULT_AMM_EVENTS
+                        .iter()
+                        .map(|event| event.to_string())
+                        .collect(),
+                }]
+            }),
+            (None, Some(packages)) => amm_packages(packages, errors),
+            (None, None) => required(
+                None,
+                "package.amm_package_id or [[package.amm]]",
+                "AMM_PACKAGE_ID",
+                errors,
+            ),
+        };
+
+        let amm_module = parse(
+            self.package
+                .amm_module
+                .as_deref()
+                .unwrap_or(DEFAULT_AMM_MODULE),
+            "package.amm_module",
+            errors,
+        );
+
+        let mut dex_venues = Vec::new();
+        for (venue, package_id, field) in [
+            (
+                Venue::Cetus,
+                self.dex.cetus_package_id,
+                "dex.cetus_package_id",
+            ),
+            (
+                Venue::Turbos,
+                self.dex.turbos_package_id,
+                "dex.turbos_package_id",
+            ),
+            (
+                Venue::DeepBook,
+                self.dex.deepbook_package_id,
+                "dex.deepbook_package_id",
+            ),
+        ] {
+            if let Some(package_id) = package_id.and_then(|id| parse(&id, field, errors)) {
+                dex_venues.push(DexVenue { venue, package_id });
+            }
+        }
+
+        let db_url = required(self.db.url, "db.url", "DB_URL", errors);
+        let username = required(self.db.username, "db.username", "DB_USERNAME", errors);
+        let password = required(self.db.password, "db.password", "DB_PASSWORD", errors);
+        let namespace = required(self.db.namespace, "db.namespace", "DB_NAMESPACE", errors);
+        let db_name = required(self.db.name, "db.name", "DB_NAME", errors);
+        let client_id = required(self.db.client_id, "db.client_id", "DB_CLIENT_ID", errors);
+        let client_password = required(
+            self.db.client_password,
+            "db.client_password",
+            "DB_CLIENT_PASSWORD",
+            errors,
+        );
+
+        let addr = parse(
+            self.api.addr.as_deref().unwrap_or(DEFAULT_API_ADDR),
+            "api.addr",
+            errors,
+        );
+        let ops_addr = parse(
+            self.ops.addr.as_deref().unwrap_or(DEFAULT_OPS_ADDR),
+            "ops.addr",
+            errors,
+        );
+        let max_lag_secs = positive(
+            self.ops.max_lag_secs.unwrap_or(DEFAULT_MAX_LAG_SECS),
+            "ops.max_lag_secs",
+            errors,
+        );
+
+        let event_capacity = positive(
+            self.channel
+                .event_capacity
+                .unwrap_or(DEFAULT_EVENT_CHANNEL_CAPACITY),
+            "channel.event_capacity",
+            errors,
+        );
+        let update_capacity = positive(
+            self.channel
+                .update_capacity
+                .unwrap_or(DEFAULT_UPDATE_CHANNEL_CAPACITY),
+            "channel.update_capacity",
+            errors,
+        );
+
+        let initial_backoff_ms = positive(
+            self.supervisor
+                .initial_backoff_ms
+                .unwrap_or(DEFAULT_INITIAL_BACKOFF_MS),
+            "supervisor.initial_backoff_ms",
+            errors,
+        );
+        let max_backoff_secs = positive(
+            self.supervisor
+                .max_backoff_secs
+                .unwrap_or(DEFAULT_MAX_BACKOFF_SECS),
+            "supervisor.max_backoff_secs",
+            errors,
+        );
+        let stable_after_secs = positive(
+            self.supervisor
+                .stable_after_secs
+                .unwrap_or(DEFAULT_STABLE_AFTER_SECS),
+            "supervisor.stable_after_secs",
+            errors,
+        );
+
+        let max_attempts = positive(
+            self.retry
+                .max_attempts
+                .unwrap_or(DEFAULT_RETRY_MAX_ATTEMPTS),
+            "retry.max_attempts",
+            errors,
+        );
+        let retry_initial_backoff_ms = positive(
+            self.retry
+                .initial_backoff_ms
+                .unwrap_or(DEFAULT_RETRY_INITIAL_BACKOFF_MS),
+            "retry.initial_backoff_ms",
+            errors,
+        );
+        let retry_max_backoff_secs = positive(
+            self.retry
+                .max_backoff_secs
+                .unwrap_or(DEFAULT_RETRY_MAX_BACKOFF_SECS),
+            "retry.max_backoff_secs",
+            errors,
+        );
+        let reconcile_interval_secs = positive(
+            self.reconcile
+                .interval_secs
+                .unwrap_or(DEFAULT_RECONCILE_INTERVAL_SECS),
+            "reconcile.interval_secs",
+            errors,
+        );
+
+        let log_level = self
+            .log
+            .level
+            .unwrap_or_else(|| DEFAULT_LOG_LEVEL.to_string());
+        if let Err(e) = EnvFilter::try_new(&log_level) {
+            errors.push(format!("log.level: invalid value {:?}: {}", log_level, e));
+        }
+        let log_format = parse(
+            self.log.format.as_deref().unwrap_or("text"),
+            "log.format",
+            errors,
+        );
+
+        let otlp = match self.otlp.endpoint {
+            Some(endpoint) => url(
+                Some(endpoint),
+                None,
+                "otlp.endpoint",
+                &["http://", "https://"],
+                errors,
+            )
+            .map(|endpoint| {
+                Some(OtlpConfig {
+                    endpoint,
+                    service_name: self
+                        .otlp
+                        .service_name
+                        .unwrap_or_else(|| DEFAULT_SERVICE_NAME.to_string()),
+                })
+            }),
+            None => Some(None),
+        };
+
+        Some(Config {
+            network: NetworkConfig {
+                build: build?,
+                endpoints,
+                ws_ping_interval: Duration::from_secs(ws_ping_interval_secs?),
+                request_timeout: Duration::from_secs(request_timeout_secs?),
+                health_check_interval: Duration::from_secs(health_check_interval_secs?),
+            },
+            package: PackageConfig {
+                amm_packages: amm_packages?,
+                amm_module: amm_module?,
+            },
+            dex: DexConfig { venues: dex_venues },
+            db: DbConfig {
+                url: db_url?,
+                username: username?,
+                password: password?,
+                namespace: namespace?,
+                name: db_name?,
+                client_id: client_id?,
+                client_password: client_password?,
+            },
+            api: ApiConfig {
+                addr: addr?,
+                graphiql: self.api.graphiql.unwrap_or(false),
+            },
+            ops: OpsConfig {
+                addr: ops_addr?,
+                max_lag: Duration::from_secs(max_lag_secs?),
+            },
+            channel: ChannelConfig {
+                event_capacity: event_capacity?,
+                update_capacity: update_capacity?,
+            },
+            supervisor: SupervisorConfig {
+                max_restarts: self.supervisor.max_restarts.unwrap_or(DEFAULT_MAX_RESTARTS),
+                initial_backoff: Duration::from_millis(initial_backoff_ms?),
+                max_backoff: Duration::from_secs(max_backoff_secs?),
+                stable_after: Duration::from_secs(stable_after_secs?),
+            },
+            retry: RetryConfig {
+                max_attempts: max_attempts?,
+                initial_backoff: Duration::from_millis(retry_initial_backoff_ms?),
+                max_backoff: Duration::from_secs(retry_max_backoff_secs?),
+            },
+            reconcile: ReconcileConfig {
+                interval: Duration::from_secs(reconcile_interval_secs?),
+            },
+            log: LogConfig {
+                level: log_level,
+                format: log_format?,
+            },
+            otlp: otlp?,
+        })
+    }
+}
+
+/// 환경 변수 조회. 테스트는 프로세스 환경 대신 정해 둔 값을 넘깁니다.
+type EnvLookup<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+fn env_override<T>(target: &mut Option<T>, key: &str, env: EnvLookup, errors: &mut Vec<String>)
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    if let Some(value) = env(key) {
+        match value.parse() {
+            Ok(value) => *target = Some(value),
+            Err(e) => errors.push(format!("{}: invalid value {:?}: {}", key, value, e)),
+        }
+    }
+}
+
+fn required<T>(
+    value: Option<T>,
+    field: &str,
+    env_key: &str,
+    errors: &mut Vec<String>,
+) -> Option<T> {
+    if value.is_none() {
+        errors.push(format!(
+            "{}: missing (set it in the config file or {})",
+            field, env_key
+        ));
+    }
+    value
+}
+
+fn parse<T>(value: &str, field: &str, errors: &mut Vec<String>) -> Option<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    match value.parse() {
+        Ok(value) => Some(value),
+        Err(e) => {
+            errors.push(format!("{}: invalid value {:?}: {}", field, value, e));
+            None
+        }
+    }
+}
+
+/// 설정된 URL 또는 네트워크 기본값을 사용하고 scheme을 검사합니다.
+fn url(
+    value: Option<String>,
+    preset: Option<&str>,
+    field: &str,
+    schemes: &[&str],
+    errors: &mut Vec<String>,
+) -> Option<String> {
+    let Some(url) = value.or_else(|| preset.map(str::to_string)) else {
+        errors.push(format!(
+            "{}: missing (required for a custom network)",
+            field
+        ));
+        return None;
+    };
+    if !schemes.iter().any(|scheme| url.starts_with(scheme)) {
+        errors.push(format!(
+            "{}: expected a URL starting with one of {:?}, got {:?}",
+            field, schemes, url
+        ));
+        return None;
+    }
+    Some(url)
+}
+
+fn amm_packages(packages: Vec<RawAmmPackage>, errors: &mut Vec<String>) -> Option<Vec<AmmPackage>> {
+    if packages.is_empty() {
+        errors.push("package.amm: at least one package is required".to_string());
+        return None;
+    }
+
+    let mut amm_packages = Vec::new();
+    for (index, package) in packages.into_iter().enumerate() {
+        let field = format!("package.amm[{}]", index);
+        let events = package.events.unwrap_or_else(|| {
+            DEFAULT_AMM_EVENTS
+                .iter()
+                .map(|event| event.to_string())
+                .collect()
+        });
+        for event in &events {
+            if !AMM_EVENTS.contains(&event.as_str()) {
+                errors.push(format!(
+                    "{}.events: unknown event {:?}, expected one of {:?}",
+                    field, event, AMM_EVENTS
+                ));
+            }
+        }
+        if let Some(package_id) = parse(
+            &package.package_id,
+            &format!("{}.package_id", field),
+            errors,
+        ) {
+            if amm_packages
+                .iter()
+                .any(|known: &AmmPackage| known.package_id == package_id)
+            {
+                errors.push(format!(
+                    "{}.package_id: duplicate package {}",
+                    field, package_id
+                ));
+            }
+            amm_packages.push(AmmPackage {
+                package_id,
+                version: package.version,
+                events,
+            });
+        }
+    }
+    Some(amm_packages)
+}
+
+fn auth_pair(
+    username: Option<String>,
+    password: Option<String>,
+    field: &str,
+    errors: &mut Vec<String>,
+) -> Option<(String, String)> {
+    match (username, password) {
+        (Some(username), Some(password)) => Some((username, password)),
+        (None, None) => None,
+        _ => {
+            errors.push(format!(
+                "{}: username and password must be set together",
+                field
+            ));
+            None
+        }
+    }
+}
+
+fn headers(
+    headers: Option<RawHeaders>,
+    field: &str,
+    errors: &mut Vec<String>,
+) -> Vec<(HeaderName, HeaderValue)> {
+    headers
+        .map_or_else(BTreeMap::new, |headers| headers.0)
+        .iter()
+        .filter_map(|(name, value)| match rpc_proxy::parse_header(name, value) {
+            Ok(header) => Some(header),
+            Err(e) => {
+                errors.push(format!("{}: {}", field, e));
+                None
+            }
+        })
+        .collect()
+}
+
+fn positive<T>(value: T, field: &str, errors: &mut Vec<String>) -> Option<T>
+where
+    T: PartialOrd + Default,
+{
+    if value > T::default() {
+        Some(value)
+    } else {
+        errors.push(format!("{}: must be greater than zero", field));
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    const MINIMAL: &str = r#"
+        [network]
+        build = "testnet"
+
+        [package]
+        amm_package_id = "0x2"
+
+        [db]
+        url = "db.example.com"
+        username = "root"
+        password = "secret"
+        namespace = "gmi"
+        name = "gmi"
+        client_id = "viewer"
+        client_password = "viewer"
+    "#;
+
+    fn raw(content: &str) -> RawConfig {
+        toml::from_str(content).unwrap()
+    }
+
+    /// 프로세스 환경 대신 주어진 값만 읽어 환경 변수를 적용합니다.
+    fn apply_env(raw: &mut RawConfig, vars: &[(&str, &str)], errors: &mut Vec<String>) {
+        let env: HashMap<&str, &str> = vars.iter().copied().collect();
+        raw.apply_env(&|key| env.get(key).map(|value| value.to_string()), errors);
+    }
+
+    #[test]
+    fn env_overrides_file_values() {
+        let mut raw = raw(MINIMAL);
+        let mut errors = Vec::new();
+        apply_env(
+            &mut raw,
+            &[
+                ("DB_URL", "other.example.com"),
+                ("EVENT_CHANNEL_CAPACITY", "7"),
+                ("SUI_RPC_HEADERS", "x-api-key=secret, x-client=gmi"),
+                ("OPS_MAX_LAG_SECS", "60"),
+            ],
+            &mut errors,
+        );
+        let config = raw.validate(&mut errors).unwrap();
+
+        assert!(errors.is_empty(), "{:?}", errors);
+        let headers = &config.network.endpoints[0].headers;
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers[0].0, "x-api-key");
+        assert_eq!(headers[0].1, "secret");
+        assert_eq!(config.ops.max_lag, Duration::from_secs(60));
+        assert_eq!(config.db.url, "other.example.com");
+        assert_eq!(config.db.username, "root");
+        assert_eq!(config.channel.event_capacity, 7);
+    }
+
+    #[test]
+    fn invalid_env_value_is_reported_with_its_key() {
+        let mut errors = Vec::new();
+        apply_env(
+            &mut raw(MINIMAL),
+            &[("EVENT_CHANNEL_CAPACITY", "many")],
+            &mut errors,
+        );
+
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(errors[0].starts_with("EVENT_CHANNEL_CAPACITY: invalid value \"many\""));
+    }
+
+    #[test]
+    fn reports_every_error_at_once() {
+        let mut raw = raw(r#"
+            [network]
+            build = "custom"
+
+            [channel]
+            event_capacity = 0
+
+            [log]
+            format = "yaml"
+            "#);
+        raw.dex.cetus_package_id = Some("not an id".to_string());
+        let mut errors = Vec::new();
+
+        assert!(raw.validate(&mut errors).is_none());
+        for field in [
+            "network.http_url",
+            "network.ws_url",
+            "package.amm_package_id or [[package.amm]]",
+            "dex.cetus_package_id",
+            "db.url",
+            "db.username",
+            "db.password",
+            "db.namespace",
+            "db.name",
+            "db.client_id",
+            "db.client_password",
+            "channel.event_capacity",
+            "log.format",
+        ] {
+            assert!(
+                errors.iter().any(|e| e.starts_with(&format!("{}:", field))),
+                "no error for {} in {:?}",
+                field,
+                errors
+            );
+        }
+        let message = ConfigError(errors).to_string();
+        assert!(message.starts_with("invalid configuration (13 errors)"));
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        let unknown_key = format!("{}\n[ops]\nmax_lag = 30\n", MINIMAL);
+        let unknown_section = format!("{}\n[metrics]\naddr = \"0.0.0.0:9100\"\n", MINIMAL);
+        let unknown_fallback_key = r#"
+            [network]
+            fallbacks = [{ http_url = "https://a", ws_url = "wss://a", token = "x" }]
+        "#;
+
+        for content in [
+            unknown_key.as_str(),
+            unknown_section.as_str(),
+            unknown_fallback_key,
+        ] {
+            let error = toml::from_str::<RawConfig>(content).unwrap_err();
+            assert!(error.to_string().contains("unknown field"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn minimal_file_uses_defaults() {
+        let mut errors = Vec::new();
+        let config = raw(MINIMAL).validate(&mut errors).unwrap();
+
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(config.network.endpoints.len(), 1);
+        assert_eq!(config.package.amm_packages[0].version, 1);
+        assert!(!config.package.amm_packages[0].handles("AddLiquidityEvent"));
+        assert!(!config.package.amm_packages[0].handles("MigratePoolEvent"));
+        assert!(config.dex.venues.is_empty());
+        assert_eq!(
+            config.channel.event_capacity,
+            DEFAULT_EVENT_CHANNEL_CAPACITY
+        );
+        assert!(config.otlp.is_none());
+        assert_eq!(
+            config.ops.max_lag,
+            Duration::from_secs(DEFAULT_MAX_LAG_SECS)
+        );
+    }
+}
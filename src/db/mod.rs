@@ -1,58 +1,77 @@
 pub mod model;
 
+use crate::config::DbConfig;
 use crate::db::model::{CreatePoolEvent, PoolInfo};
-use crate::env::DBEnv;
+use crate::error::{Error, OrMissing, Result};
+use crate::metrics;
 use crate::utils::convert_chart_timestamp;
 // use anyhow::Result;
 use once_cell::sync::Lazy;
+use rust_decimal::Decimal;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
 use sui_sdk::rpc_types::SuiCoinMetadata;
 use sui_sdk::SuiClient;
-use surrealdb::engine::remote::ws::Wss;
 use surrealdb::sql::Thing;
 use surrealdb::Response;
 use surrealdb::{
-    engine::remote::ws::{Client, Ws},
+    engine::any::{self, Any},
     opt::auth::Root,
-    Result, Surreal,
+    Surreal,
 };
-use tracing::info;
+use tracing::{debug, info, instrument, warn};
 
-use self::model::{Chart, ChartData, Swap, SwapEvent, Token, Trade, TradeData};
+use self::model::{
+    Account, AccountBalance, BalanceChange, Chart, ChartData, DeadLetter, EventCursor, Lifecycle,
+    LiquidityAction, Swap, SwapEvent, Token, Trade, TradeData,
+};
 
 static POOL_INFO: &str = "POOL_INFO";
 
 static TOKEN: &str = "TOKEN";
 static TRADE_DATA: &str = "TRADE_DATA";
 static CHART_DATA: &str = "CHART_DATA";
-static DB: Lazy<Surreal<Client>> = Lazy::new(|| Surreal::init());
+static ACCOUNT: &str = "ACCOUNT";
+static EVENT_CURSOR: &str = "EVENT_CURSOR";
+static DEAD_LETTER: &str = "DEAD_LETTER";
+static PENDING_RETRY: &str = "PENDING_RETRY";
+static LIQUIDITY: &str = "LIQUIDITY";
+static ACCOUNT_BALANCE: &str = "ACCOUNT_BALANCE";
+static BALANCE_CHANGE: &str = "BALANCE_CHANGE";
+/// `BALANCE_CHANGE`에서 한 계정·코인의 기록
+static BALANCE_CHANGE_OF_ACCOUNT: &str = "account = $account AND coinType = $coin_type";
+/// `BALANCE_CHANGE`에서 ($timestamp, $version)보다 뒤의 기록
+static BALANCE_CHANGE_AFTER: &str =
+    "(timestamp > $timestamp OR (timestamp = $timestamp AND version > $version))";
+static DB: Lazy<Surreal<Any>> = Lazy::new(|| Surreal::init());
 
 #[derive(Debug, Clone)]
 pub struct Database {
-    db: Surreal<Client>,
+    db: Surreal<Any>,
 }
 
 impl Database {
     /// 새로운 Database 인스턴스를 생성합니다.
-    pub async fn new() -> Result<Self> {
-        let env = DBEnv::new();
+    pub async fn new(config: &DbConfig) -> Result<Self> {
         info!("db Connect start!");
 
-        DB.connect::<Wss>(&env.db_url).await?;
+        DB.connect(format!("wss://{}", config.url)).await?;
 
         info!("DB Connect end!");
         info!("DB Signin start!");
         DB.signin(Root {
-            username: env.username.as_str(),
-            password: env.password.as_str(),
+            username: config.username.as_str(),
+            password: config.password.as_str(),
         })
         .await?;
         info!("DB Signin Finish!");
-        DB.use_ns(env.name_space).use_db(env.db_name).await?;
+        DB.use_ns(config.namespace.as_str())
+            .use_db(config.name.as_str())
+            .await?;
 
         let sql = format!(
             "DEFINE USER {} ON DATABASE PASSWORD \"{}\" ROLES VIEWER",
-            env.db_client_id, env.db_client_password
+            config.client_id, config.client_password
         );
         let result = DB.query(sql).await?;
         match result.check() {
@@ -60,185 +79,775 @@ impl Database {
                 info!("User defined successfully");
             }
             Err(err) => {
-                info!("User already defined {}", err);
+                info!(error = %err, "User already defined");
             }
         }
         Ok(Self { db: DB.clone() })
     }
 
-    pub async fn save_pool(&self, create_pool_event: CreatePoolEvent) -> Result<()> {
-        let pool = PoolInfo::new(create_pool_event);
+    /// 빈 메모리 DB를 엽니다. 테스트용이며 surrealdb의 `kv-mem` 기능이 켜진 빌드에서만 동작합니다.
+    pub async fn in_memory() -> Result<Self> {
+        let db = any::connect("mem://").await?;
+        db.use_ns("test").use_db("test").await?;
+        Ok(Self { db })
+    }
 
-        let pool_opt: Option<PoolInfo> = self
-            .db
-            .create((POOL_INFO, pool.coin_type.as_str()))
-            .content(pool)
-            .await?;
-        // info!("Create Pool {:?}", pool_opt);
-        Ok(())
+    /// DB 연결 상태를 확인합니다.
+    #[instrument(skip_all)]
+    pub async fn ping(&self) -> Result<()> {
+        let _timer = metrics::db_timer("ping");
+        Ok(self.db.health().await?)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn save_pool(&self, create_pool_event: CreatePoolEvent) -> Result<PoolInfo> {
+        let _timer = metrics::db_timer("save_pool");
+        let pool = PoolInfo::new(create_pool_event)?;
+        let id = pool.coin_type.clone();
+
+        // 스왑이 먼저 처리되어 저장한 reserve와 상태 변경은 남깁니다.
+        self.upsert(
+            POOL_INFO,
+            &id,
+            pool,
+            &[
+                "reserve_meme",
+                "reserve_sui",
+                "reserves_updated_at",
+                "lifecycle",
+            ],
+        )
+        .await
+    }
+
+    /// Pool의 reserve 값을 업데이트하고 갱신된 PoolInfo를 반환합니다.
+    #[instrument(skip_all)]
+    pub async fn update_pool_info_reserve(&self, swap_event: SwapEvent) -> Result<PoolInfo> {
+        let coin_type = swap_event.coin_type.clone().or_missing("coin_type")?;
+        let pool_id = swap_event.pool_id.to_string();
+        let package_version = swap_event.package_version;
+        let swap = Swap::new(swap_event)?;
+        self.update_pool_reserve(
+            coin_type,
+            pool_id,
+            swap.reserve_meme,
+            swap.reserve_sui,
+            swap.timestamp,
+            package_version,
+        )
+        .await
     }
 
-    /// Pool의 reserve 값을 업데이트합니다.
-    pub async fn update_pool_info_reserve(&self, swap_event: SwapEvent) -> Result<()> {
-        let coin_type = swap_event.coin_type.clone().unwrap();
-        let pool_id = swap_event.pool_id.clone();
-        let swap = Swap::new(swap_event);
+    /// Pool의 reserve 값을 주어진 값으로 바꿉니다. 저장된 Pool이 없으면 새로 만듭니다.
+    ///
+    /// 저장된 reserve보다 이전 시각의 값이면 바꾸지 않고 저장된 PoolInfo를 반환합니다.
+    /// 상태 변경 등 다른 작업이 쓴 필드를 덮어쓰지 않도록 reserve 필드만 바꿉니다.
+    #[instrument(skip_all)]
+    pub async fn update_pool_reserve(
+        &self,
+        coin_type: String,
+        pool_id: String,
+        reserve_meme: u64,
+        reserve_sui: u64,
+        timestamp: u64,
+        package_version: Option<u64>,
+    ) -> Result<PoolInfo> {
+        let _timer = metrics::db_timer("update_pool_reserve");
         let pool_info: Option<PoolInfo> = self.db.select((POOL_INFO, coin_type.as_str())).await?;
 
         match pool_info {
             Some(mut pool_info) => {
-                pool_info.reserve_meme = swap.reserve_meme;
-                pool_info.reserve_sui = swap.reserve_sui;
-                let pool_info_opt: Option<PoolInfo> = self
+                if !pool_info.update_reserves(reserve_meme, reserve_sui, timestamp) {
+                    debug!(
+                        timestamp,
+                        reserves_updated_at = pool_info.reserves_updated_at,
+                        "Skipping stale reserves"
+                    );
+                    return Ok(pool_info);
+                }
+                let mut response = self
                     .db
-                    .update((POOL_INFO, coin_type))
-                    .content(pool_info)
+                    .query("UPDATE type::thing($table, $id) SET reserve_meme = $reserve_meme, reserve_sui = $reserve_sui, reserves_updated_at = $timestamp WHERE (reserves_updated_at ?? 0) <= $timestamp")
+                    .bind(("table", POOL_INFO))
+                    .bind(("id", coin_type.as_str()))
+                    .bind(("reserve_meme", reserve_meme))
+                    .bind(("reserve_sui", reserve_sui))
+                    .bind(("timestamp", timestamp))
                     .await?;
-
-                // info!("Update PoolInfo {:?}", pool_info_opt)
+                let updated: Option<PoolInfo> = response.take(0)?;
+                match updated {
+                    Some(pool_info) => Ok(pool_info),
+                    // 조회한 뒤 더 최신 이벤트가 먼저 저장되었습니다.
+                    None => {
+                        let stored: Option<PoolInfo> =
+                            self.db.select((POOL_INFO, coin_type.as_str())).await?;
+                        stored.or_missing("pool info")
+                    }
+                }
             }
             None => {
                 let new_pool_info = PoolInfo {
                     coin_type: coin_type.clone(),
                     pool_id,
-                    reserve_meme: swap.reserve_meme,
-                    reserve_sui: swap.reserve_sui,
-                    time_stamp: swap.timestamp,
+                    reserve_meme,
+                    reserve_sui,
+                    time_stamp: timestamp,
+                    package_version,
+                    lifecycle: Lifecycle::Launching,
+                    reserves_updated_at: timestamp,
                 };
-                let pool_info_opt: Option<PoolInfo> = self
-                    .db
-                    .create((POOL_INFO, coin_type))
-                    .content(new_pool_info)
-                    .await?;
-                // info!("Create PoolInfo {:?}", pool_info_opt)
+                // 조회한 뒤 Pool 생성 이벤트가 먼저 저장했다면 그 정보와 상태는 남깁니다.
+                self.upsert(
+                    POOL_INFO,
+                    &coin_type,
+                    new_pool_info,
+                    &["pool_id", "time_stamp", "package_version", "lifecycle"],
+                )
+                .await
             }
         }
+    }
 
-        Ok(())
+    /// 체인에서 읽은 reserve로 고칩니다.
+    ///
+    /// 읽은 뒤 이벤트 처리가 reserve를 바꿨다면 그 값이 더 최신이므로 고치지 않고 None을 반환합니다.
+    #[instrument(skip_all)]
+    pub async fn set_pool_reserve(
+        &self,
+        stored: &PoolInfo,
+        reserve_meme: u64,
+        reserve_sui: u64,
+    ) -> Result<Option<PoolInfo>> {
+        let _timer = metrics::db_timer("set_pool_reserve");
+        let mut response = self
+            .db
+            .query("UPDATE type::thing($table, $id) SET reserve_meme = $reserve_meme, reserve_sui = $reserve_sui WHERE pool_id = $pool_id AND (reserves_updated_at ?? 0) = $reserves_updated_at AND reserve_meme = $stored_meme AND reserve_sui = $stored_sui")
+            .bind(("table", POOL_INFO))
+            .bind(("id", stored.coin_type.as_str()))
+            .bind(("pool_id", stored.pool_id.as_str()))
+            .bind(("reserves_updated_at", stored.reserves_updated_at))
+            .bind(("stored_meme", stored.reserve_meme))
+            .bind(("stored_sui", stored.reserve_sui))
+            .bind(("reserve_meme", reserve_meme))
+            .bind(("reserve_sui", reserve_sui))
+            .await?;
+        Ok(response.take(0)?)
     }
 
     // Swap 관련 메서드들
 
-    /// Swap 데이터를 저장합니다.
-    pub async fn save_trade_data(&self, swap_event: SwapEvent) -> Result<()> {
-        let coin_type = swap_event.coin_type.clone().unwrap();
-        let trade = Trade::new(swap_event);
-        info!("coin_type = {:?}\ntrade = {:?}\n \n", coin_type, trade);
+    /// 같은 이벤트의 거래가 이미 저장되었는지 확인합니다.
+    #[instrument(skip_all)]
+    pub async fn trade_exists(
+        &self,
+        coin_type: &str,
+        transaction_hash: &str,
+        event_seq: u64,
+    ) -> Result<bool> {
+        let _timer = metrics::db_timer("trade_exists");
+        let trades: Option<TradeData> = self.db.select((TRADE_DATA, coin_type)).await?;
+        Ok(trades.map_or(false, |trades| trades.contains(transaction_hash, event_seq)))
+    }
+
+    /// Swap 데이터를 저장하고 저장된 Trade를 반환합니다. 이미 저장된 이벤트면 None을 반환합니다.
+    #[instrument(skip_all)]
+    pub async fn save_trade_data(&self, swap_event: SwapEvent) -> Result<Option<Trade>> {
+        let _timer = metrics::db_timer("save_trade_data");
+        let coin_type = swap_event.coin_type.clone().or_missing("coin_type")?;
+        let trade = Trade::new(swap_event)?;
+        debug!(coin_type = %coin_type, trade = ?trade, "Saving trade");
 
         let trades: Option<TradeData> = self.db.select((TRADE_DATA, coin_type.as_str())).await?;
 
         match trades {
             Some(mut trade_data) => {
-                info!("Trade Update");
-                trade_data.add_trade(trade);
-                let trades: Option<TradeData> = self
+                debug!("Trade Update");
+                if !trade_data.add_trade(trade.clone()) {
+                    debug!("Trade already saved");
+                    return Ok(None);
+                }
+                let _: Option<TradeData> = self
                     .db
                     .update((TRADE_DATA, coin_type.as_str()))
                     .content(trade_data)
                     .await?;
-                // info!("update trades {:?}", trades)
             }
             None => {
                 let mut new_trade_data = TradeData::new();
-                new_trade_data.add_trade(trade);
-                info!("Trade Create");
-                let trades: Option<TradeData> = self
+                new_trade_data.add_trade(trade.clone());
+                debug!("Trade Create");
+                let _: Option<TradeData> = self
                     .db
                     .create((TRADE_DATA, coin_type.as_str()))
                     .content(new_trade_data)
                     .await?;
-
-                // info!("create trades {:?}", trades)
             }
         }
 
-        Ok(())
+        Ok(Some(trade))
     }
 
-    pub async fn save_chart_data(&self, swap_event: SwapEvent) -> Result<()> {
-        let coin_type = swap_event.coin_type.clone().unwrap();
-        let timestamp = swap_event.timestamp.unwrap();
-        let current_price = swap_event.current_price.unwrap();
+    /// 차트 데이터를 갱신하고 거래가 반영된 구간의 Chart를 반환합니다.
+    #[instrument(skip_all)]
+    pub async fn save_chart_data(&self, swap_event: SwapEvent) -> Result<Chart> {
+        let _timer = metrics::db_timer("save_chart_data");
+        let coin_type = swap_event.coin_type.clone().or_missing("coin_type")?;
+        let timestamp = swap_event.timestamp.or_missing("timestamp")?;
+        let current_price = swap_event.current_price.or_missing("current_price")?;
         // info!("timestamp is = {:?}", timestamp);
         let chart_data: Option<ChartData> =
             self.db.select((CHART_DATA, coin_type.as_str())).await?;
 
         match chart_data {
             Some(mut chart_data) => {
-                info!("Chart Update");
-                chart_data.update_latest_chart(timestamp, current_price);
-                let chart_opt: Option<ChartData> = self
+                debug!("Chart Update");
+                let chart = chart_data.apply_trade(timestamp, current_price)?;
+                let _: Option<ChartData> = self
                     .db
                     .update((CHART_DATA, coin_type.as_str()))
                     .content(chart_data)
                     .await?;
 
-                info!("Chart Update{:?}", chart_opt);
+                Ok(chart)
             }
 
             None => {
+                let latest_chart = Chart::new(timestamp, current_price);
                 let new_chart_data = ChartData {
-                    charts: vec![Chart::new(swap_event.timestamp.unwrap(), current_price)],
+                    charts: vec![latest_chart.clone()],
                 };
-                let chart_opt: Option<ChartData> = self
+                let _: Option<ChartData> = self
                     .db
                     .create((CHART_DATA, coin_type.as_str()))
                     .content(new_chart_data)
                     .await?;
-                info!("Chart Create {:?}", chart_opt);
+                debug!("Chart Create");
+                Ok(latest_chart)
             }
         }
+    }
+
+    /// 가격 변화 없이 차트에 유동성 변화를 표시하고 해당 구간 Chart를 반환합니다.
+    /// 같은 이벤트로 두 번 부르면 두 번 세므로 기록을 저장하기 전에 한 번만 부릅니다.
+    #[instrument(skip_all)]
+    pub async fn mark_chart_liquidity(
+        &self,
+        coin_type: &str,
+        timestamp: u64,
+        price: Decimal,
+    ) -> Result<Chart> {
+        let _timer = metrics::db_timer("mark_chart_liquidity");
+        let chart_data: Option<ChartData> = self.db.select((CHART_DATA, coin_type)).await?;
+        let mut chart_data = chart_data.unwrap_or(ChartData { charts: vec![] });
+        let chart = chart_data.mark_liquidity_change(timestamp, price)?;
+        let _: Option<ChartData> = self
+            .db
+            .update((CHART_DATA, coin_type))
+            .content(chart_data)
+            .await?;
+        Ok(chart)
+    }
+
+    // 유동성 관련 메서드들
+
+    /// 같은 이벤트의 유동성 공급/회수 기록이 이미 저장되었는지 확인합니다.
+    #[instrument(skip_all)]
+    pub async fn liquidity_action_exists(&self, id: &str) -> Result<bool> {
+        let _timer = metrics::db_timer("liquidity_action_exists");
+        let action: Option<LiquidityAction> = self.db.select((LIQUIDITY, id)).await?;
+        Ok(action.is_some())
+    }
+
+    /// 유동성 공급/회수 기록을 저장합니다. 같은 이벤트는 덮어씁니다.
+    #[instrument(skip_all)]
+    pub async fn save_liquidity_action(&self, action: LiquidityAction) -> Result<()> {
+        let _timer = metrics::db_timer("save_liquidity_action");
+        let _: Option<LiquidityAction> = self
+            .db
+            .update((LIQUIDITY, action.id()))
+            .content(action)
+            .await?;
+        Ok(())
+    }
+
+    /// 계정의 유동성 공급/회수 기록을 최신순으로 조회합니다.
+    #[instrument(skip_all)]
+    pub async fn get_liquidity_actions(
+        &self,
+        account: &str,
+        start: usize,
+        limit: usize,
+    ) -> Result<Vec<LiquidityAction>> {
+        let _timer = metrics::db_timer("get_liquidity_actions");
+        let mut response = self
+            .db
+            .query("SELECT * FROM type::table($table) WHERE account = $account ORDER BY updatedTimeStampAt DESC LIMIT $limit START $start")
+            .bind(("table", LIQUIDITY))
+            .bind(("account", account))
+            .bind(("limit", limit))
+            .bind(("start", start))
+            .await?;
+        let actions: Vec<LiquidityAction> = response.take(0)?;
+        Ok(actions)
+    }
+
+    /// 계정의 유동성 공급/회수 기록 개수를 조회합니다.
+    #[instrument(skip_all)]
+    pub async fn count_liquidity_actions(&self, account: &str) -> Result<usize> {
+        let _timer = metrics::db_timer("count_liquidity_actions");
+        let mut response = self
+            .db
+            .query("SELECT count() AS total FROM type::table($table) WHERE account = $account GROUP ALL")
+            .bind(("table", LIQUIDITY))
+            .bind(("account", account))
+            .await?;
+        let total: Option<usize> = response.take((0, "total"))?;
+        Ok(total.unwrap_or(0))
+    }
+
+    // 계정 잔고 관련 메서드들
+
+    #[instrument(skip_all)]
+    pub async fn get_account_balance(
+        &self,
+        coin_type: &str,
+        account: &str,
+    ) -> Result<Option<AccountBalance>> {
+        let _timer = metrics::db_timer("get_account_balance");
+        let id = AccountBalance::id(coin_type, account);
+        Ok(self.db.select((ACCOUNT_BALANCE, id.as_str())).await?)
+    }
+
+    /// 이미 반영한 트랜잭션의 잔고 변화를 조회합니다.
+    #[instrument(skip_all)]
+    pub async fn get_balance_change(
+        &self,
+        transaction_hash: &str,
+        coin_type: &str,
+        account: &str,
+    ) -> Result<Option<BalanceChange>> {
+        let _timer = metrics::db_timer("get_balance_change");
+        let id = BalanceChange::id(transaction_hash, coin_type, account);
+        Ok(self.db.select((BALANCE_CHANGE, id.as_str())).await?)
+    }
+
+    /// 트랜잭션의 잔고 변화를 계정의 기록 사이에 (`timestamp`, `version`) 순서로 끼워 넣습니다.
+    ///
+    /// 잔고는 앞선 기록의 잔고(없으면 0)에 변화를 더한 값이고, 뒤의 기록은 모두 변화만큼 옮깁니다. 계정 잔고는
+    /// 마지막 기록의 잔고입니다. 일부만 저장되면 다시 처리할 때 변화를 두 번 더하거나 잔고를 갱신하지 못하므로
+    /// 한 트랜잭션으로 쓰며, 같은 계정을 동시에 고치지 않도록 호출하는 쪽에서 계정을 잠가야 합니다.
+    ///
+    /// 저장한 기록과, 옮긴 뒤의 기록을 반환합니다.
+    #[instrument(skip_all)]
+    pub async fn insert_balance_change(
+        &self,
+        mut change: BalanceChange,
+    ) -> Result<(BalanceChange, Vec<BalanceChange>)> {
+        let _timer = metrics::db_timer("insert_balance_change");
+        let mut response = self
+            .db
+            .query(format!(
+                "SELECT * FROM type::table($table) WHERE {} AND (timestamp < $timestamp OR (timestamp = $timestamp AND version < $version)) ORDER BY timestamp DESC, version DESC LIMIT 1",
+                BALANCE_CHANGE_OF_ACCOUNT
+            ))
+            .query(format!(
+                "SELECT * FROM type::table($table) WHERE {} AND {} ORDER BY timestamp, version",
+                BALANCE_CHANGE_OF_ACCOUNT, BALANCE_CHANGE_AFTER
+            ))
+            .bind(("table", BALANCE_CHANGE))
+            .bind(("account", change.account.as_str()))
+            .bind(("coin_type", change.coin_type.as_str()))
+            .bind(("timestamp", change.timestamp))
+            .bind(("version", change.version))
+            .await?;
+        let previous: Option<BalanceChange> = response.take(0)?;
+        let mut later: Vec<BalanceChange> = response.take(1)?;
+        change.balance = previous.map_or(0, |previous| previous.balance) + change.amount;
+        for later in &mut later {
+            later.balance += change.amount;
+        }
+        let latest = later.last().unwrap_or(&change).account_balance();
+
+        self.db
+            .query("BEGIN TRANSACTION")
+            .query(format!(
+                "UPDATE type::table($change_table) SET balance += $amount WHERE {} AND {}",
+                BALANCE_CHANGE_OF_ACCOUNT, BALANCE_CHANGE_AFTER
+            ))
+            .query("UPDATE type::thing($change_table, $change_id) CONTENT $change")
+            .query("UPDATE type::thing($balance_table, $balance_id) CONTENT $balance")
+            .query("COMMIT TRANSACTION")
+            .bind(("change_table", BALANCE_CHANGE))
+            .bind(("account", change.account.as_str()))
+            .bind(("coin_type", change.coin_type.as_str()))
+            .bind(("timestamp", change.timestamp))
+            .bind(("version", change.version))
+            .bind(("amount", change.amount))
+            .bind((
+                "change_id",
+                BalanceChange::id(&change.transaction_hash, &change.coin_type, &change.account),
+            ))
+            .bind(("change", &change))
+            .bind(("balance_table", ACCOUNT_BALANCE))
+            .bind((
+                "balance_id",
+                AccountBalance::id(&change.coin_type, &change.account),
+            ))
+            .bind(("balance", latest))
+            .await?
+            .check()?;
+        Ok((change, later))
+    }
 
+    /// 계정의 거래에 기록된 거래 직후 잔고를 트랜잭션별로 바꿉니다.
+    #[instrument(skip_all)]
+    pub async fn update_trade_balances(
+        &self,
+        coin_type: &str,
+        account: &str,
+        balances: &HashMap<String, u64>,
+    ) -> Result<()> {
+        let _timer = metrics::db_timer("update_trade_balances");
+        let trade_data: Option<TradeData> = self.db.select((TRADE_DATA, coin_type)).await?;
+        let Some(mut trade_data) = trade_data else {
+            return Ok(());
+        };
+        let mut changed = false;
+        for trade in trade_data
+            .trades
+            .iter_mut()
+            .filter(|trade| trade.account == account)
+        {
+            if let Some(balance) = balances.get(&trade.transaction_hash) {
+                changed |= trade.account_meme_balance != Some(*balance);
+                trade.account_meme_balance = Some(*balance);
+            }
+        }
+        if changed {
+            self.set_field(TRADE_DATA, coin_type, "trades", trade_data.trades)
+                .await?;
+        }
         Ok(())
     }
 
     // Token 관련 메서드들
 
+    #[instrument(skip_all)]
     pub async fn save_token(
         &self,
         create_pool_event: CreatePoolEvent,
         metadata: SuiCoinMetadata,
         coin_type: String,
         total_supply: u64,
-    ) -> Result<()> {
+    ) -> Result<Token> {
+        let _timer = metrics::db_timer("save_token");
         let CreatePoolEvent {
-            timestamp, digest, ..
+            timestamp,
+            digest,
+            package_version,
+            ..
         } = create_pool_event;
         let token = Token::new(
             metadata,
             coin_type.clone(),
             total_supply,
-            timestamp.unwrap(),
-            digest.unwrap(),
+            timestamp.or_missing("timestamp")?,
+            digest.or_missing("digest")?,
+            package_version,
         );
 
-        let token_opt: Option<Token> = self
-            .db
-            .create(("TOKEN", token.coin_type.as_str()))
-            .content(token)
-            .await?;
-        // info!("Save Token {:?}", token_opt);
-        Ok(())
+        // 다시 처리해도 그동안 저장한 최근 거래 시각과 상태는 남깁니다.
+        self.upsert(TOKEN, &coin_type, token, &["recent_trade", "lifecycle"])
+            .await
     }
 
+    #[instrument(skip_all)]
     pub async fn update_token_recent_trade(&self, coin_type: String, timestamp: u64) -> Result<()> {
+        let _timer = metrics::db_timer("update_token_recent_trade");
         let token: Option<Token> = self.db.select((TOKEN, coin_type.as_str())).await?;
 
         match token {
             Some(mut token) => {
                 token.update_recent_trade(timestamp);
-                let token_opt: Option<Token> = self
-                    .db
-                    .update((TOKEN, coin_type.as_str()))
-                    .content(token)
+                self.set_field(TOKEN, &coin_type, "recent_trade", token.recent_trade)
                     .await?;
-                // info!("Update Token {:?}", token_opt);
             }
             None => {
-                info!("Token not found");
+                warn!(coin_type = %coin_type, "Token not found");
             }
         }
 
         Ok(())
     }
+
+    /// Token과 Pool의 상태를 다음 단계로 바꾸고 적용된 상태를 반환합니다.
+    ///
+    /// 이미 같거나 이후 단계라면 저장된 상태를 그대로 둡니다.
+    #[instrument(skip_all)]
+    pub async fn advance_lifecycle(
+        &self,
+        coin_type: &str,
+        lifecycle: Lifecycle,
+    ) -> Result<Lifecycle> {
+        let _timer = metrics::db_timer("advance_lifecycle");
+        let mut current = None;
+
+        let token: Option<Token> = self.db.select((TOKEN, coin_type)).await?;
+        match token {
+            Some(mut token) => {
+                if token.lifecycle.advance(lifecycle.clone()) {
+                    self.set_field(TOKEN, coin_type, "lifecycle", &token.lifecycle)
+                        .await?;
+                }
+                current = Some(token.lifecycle);
+            }
+            None => warn!(coin_type = %coin_type, "Token not found"),
+        }
+
+        let pool_info: Option<PoolInfo> = self.db.select((POOL_INFO, coin_type)).await?;
+        match pool_info {
+            Some(mut pool_info) => {
+                if pool_info.lifecycle.advance(lifecycle.clone()) {
+                    self.set_field(POOL_INFO, coin_type, "lifecycle", &pool_info.lifecycle)
+                        .await?;
+                }
+                current = Some(pool_info.lifecycle);
+            }
+            None => warn!(coin_type = %coin_type, "Pool not found"),
+        }
+
+        Ok(current.unwrap_or(lifecycle))
+    }
+
+    /// 저장된 레코드의 한 필드만 바꿉니다. 다른 작업이 같은 레코드의 나머지 필드를 바꿔도 덮어쓰지 않습니다.
+    /// 레코드가 없으면 만들고, 있으면 `keep`에 든 필드는 저장된 값을 남기고 나머지 필드를 바꿉니다.
+    async fn upsert<T: Serialize + DeserializeOwned>(
+        &self,
+        table: &'static str,
+        id: &str,
+        record: T,
+        keep: &[&str],
+    ) -> Result<T> {
+        let value = serde_json::to_value(&record).map_err(|e| Error::parse("record", e))?;
+        let fields = value
+            .as_object()
+            .or_missing("record fields")?
+            .keys()
+            .map(|field| match keep.contains(&field.as_str()) {
+                true => format!("{0} = {0} ?? $record.{0}", field),
+                false => format!("{0} = $record.{0}", field),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut response = self
+            .db
+            .query(format!("UPDATE type::thing($table, $id) SET {}", fields))
+            .bind(("table", table))
+            .bind(("id", id))
+            .bind(("record", value))
+            .await?;
+        let stored: Option<T> = response.take(0)?;
+        stored.or_missing("upserted record")
+    }
+
+    async fn set_field<T: Serialize>(
+        &self,
+        table: &'static str,
+        id: &str,
+        field: &'static str,
+        value: T,
+    ) -> Result<()> {
+        self.db
+            .query(format!(
+                "UPDATE type::thing($table, $id) SET {} = $value",
+                field
+            ))
+            .bind(("table", table))
+            .bind(("id", id))
+            .bind(("value", value))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    // Cursor 관련 메서드들
+
+    /// 패키지별 마지막으로 처리한 이벤트 위치를 저장합니다.
+    #[instrument(skip_all)]
+    pub async fn save_event_cursor(&self, cursor: EventCursor) -> Result<()> {
+        let _timer = metrics::db_timer("save_event_cursor");
+        let _: Option<EventCursor> = self
+            .db
+            .update((EVENT_CURSOR, cursor.package_id.as_str()))
+            .content(cursor)
+            .await?;
+        Ok(())
+    }
+
+    #[instrument(skip_all)]
+    pub async fn get_event_cursor(&self, package_id: &str) -> Result<Option<EventCursor>> {
+        let _timer = metrics::db_timer("get_event_cursor");
+        Ok(self.db.select((EVENT_CURSOR, package_id)).await?)
+    }
+
+    // Dead letter 관련 메서드들
+
+    /// 재시도 횟수를 넘긴 이벤트를 저장합니다. 같은 이벤트는 덮어씁니다.
+    #[instrument(skip_all)]
+    pub async fn save_dead_letter(&self, dead_letter: DeadLetter) -> Result<()> {
+        let _timer = metrics::db_timer("save_dead_letter");
+        let _: Option<DeadLetter> = self
+            .db
+            .update((DEAD_LETTER, dead_letter.id()))
+            .content(dead_letter)
+            .await?;
+        Ok(())
+    }
+
+    #[instrument(skip_all)]
+    pub async fn get_dead_letters(&self) -> Result<Vec<DeadLetter>> {
+        let _timer = metrics::db_timer("get_dead_letters");
+        Ok(self.db.select(DEAD_LETTER).await?)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn delete_dead_letter(&self, id: &str) -> Result<()> {
+        let _timer = metrics::db_timer("delete_dead_letter");
+        let _: Option<DeadLetter> = self.db.delete((DEAD_LETTER, id)).await?;
+        Ok(())
+    }
+
+    /// 재시도를 기다리는 이벤트를 저장합니다. 같은 이벤트는 덮어씁니다.
+    #[instrument(skip_all)]
+    pub async fn save_pending_retry(&self, retry: DeadLetter) -> Result<()> {
+        let _timer = metrics::db_timer("save_pending_retry");
+        let _: Option<DeadLetter> = self
+            .db
+            .update((PENDING_RETRY, retry.id()))
+            .content(retry)
+            .await?;
+        Ok(())
+    }
+
+    #[instrument(skip_all)]
+    pub async fn get_pending_retries(&self) -> Result<Vec<DeadLetter>> {
+        let _timer = metrics::db_timer("get_pending_retries");
+        Ok(self.db.select(PENDING_RETRY).await?)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn delete_pending_retry(&self, id: &str) -> Result<()> {
+        let _timer = metrics::db_timer("delete_pending_retry");
+        let _: Option<DeadLetter> = self.db.delete((PENDING_RETRY, id)).await?;
+        Ok(())
+    }
+
+    // 조회 메서드들
+
+    /// 최근 생성된 순서로 Token 목록을 조회합니다.
+    #[instrument(skip_all)]
+    pub async fn get_tokens(&self, start: usize, limit: usize) -> Result<Vec<Token>> {
+        let _timer = metrics::db_timer("get_tokens");
+        let mut response = self
+            .db
+            .query("SELECT * FROM type::table($table) ORDER BY create_time DESC LIMIT $limit START $start")
+            .bind(("table", TOKEN))
+            .bind(("limit", limit))
+            .bind(("start", start))
+            .await?;
+        let tokens: Vec<Token> = response.take(0)?;
+        Ok(tokens)
+    }
+
+    /// 저장된 Token 개수를 조회합니다.
+    #[instrument(skip_all)]
+    pub async fn count_tokens(&self) -> Result<usize> {
+        let _timer = metrics::db_timer("count_tokens");
+        let mut response = self
+            .db
+            .query("SELECT count() AS total FROM type::table($table) GROUP ALL")
+            .bind(("table", TOKEN))
+            .await?;
+        let total: Option<usize> = response.take((0, "total"))?;
+        Ok(total.unwrap_or(0))
+    }
+
+    /// 이름 또는 심볼에 검색어가 포함된 Token 목록을 조회합니다.
+    #[instrument(skip_all)]
+    pub async fn search_tokens(
+        &self,
+        search: &str,
+        start: usize,
+        limit: usize,
+    ) -> Result<Vec<Token>> {
+        let _timer = metrics::db_timer("search_tokens");
+        let mut response = self
+            .db
+            .query("SELECT * FROM type::table($table) WHERE string::lowercase(name) CONTAINS $search OR string::lowercase(symbol) CONTAINS $search ORDER BY create_time DESC LIMIT $limit START $start")
+            .bind(("table", TOKEN))
+            .bind(("search", search.to_lowercase()))
+            .bind(("limit", limit))
+            .bind(("start", start))
+            .await?;
+        let tokens: Vec<Token> = response.take(0)?;
+        Ok(tokens)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn get_token(&self, coin_type: &str) -> Result<Option<Token>> {
+        let _timer = metrics::db_timer("get_token");
+        Ok(self.db.select((TOKEN, coin_type)).await?)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn get_pool_info(&self, coin_type: &str) -> Result<Option<PoolInfo>> {
+        let _timer = metrics::db_timer("get_pool_info");
+        Ok(self.db.select((POOL_INFO, coin_type)).await?)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn get_pool_infos(&self) -> Result<Vec<PoolInfo>> {
+        let _timer = metrics::db_timer("get_pool_infos");
+        Ok(self.db.select(POOL_INFO).await?)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn get_trade_data(&self, coin_type: &str) -> Result<Option<TradeData>> {
+        let _timer = metrics::db_timer("get_trade_data");
+        Ok(self.db.select((TRADE_DATA, coin_type)).await?)
+    }
+
+    /// 최신순으로 저장된 거래 중 `start`번째부터 `limit`개와 전체 거래 수를 조회합니다.
+    #[instrument(skip_all)]
+    pub async fn get_trades(
+        &self,
+        coin_type: &str,
+        start: usize,
+        limit: usize,
+    ) -> Result<(Vec<Trade>, usize)> {
+        let _timer = metrics::db_timer("get_trades");
+        let mut response = self
+            .db
+            .query("SELECT array::slice(trades, $start, $limit) AS trades, array::len(trades) AS total FROM type::thing($table, $id)")
+            .bind(("table", TRADE_DATA))
+            .bind(("id", coin_type))
+            .bind(("start", start))
+            .bind(("limit", limit))
+            .await?;
+        let trades: Option<Vec<Trade>> = response.take((0, "trades"))?;
+        let total: Option<usize> = response.take((0, "total"))?;
+        Ok((trades.unwrap_or_default(), total.unwrap_or(0)))
+    }
+
+    #[instrument(skip_all)]
+    pub async fn get_chart_data(&self, coin_type: &str) -> Result<Option<ChartData>> {
+        let _timer = metrics::db_timer("get_chart_data");
+        Ok(self.db.select((CHART_DATA, coin_type)).await?)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn get_account(&self, account: &str) -> Result<Option<Account>> {
+        let _timer = metrics::db_timer("get_account");
+        Ok(self.db.select((ACCOUNT, account)).await?)
+    }
 }
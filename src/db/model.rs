@@ -3,9 +3,19 @@ use std::str::FromStr;
 use chrono::{DateTime, NaiveDateTime, TimeZone, Timelike, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use sui_sdk::{rpc_types::SuiCoinMetadata, types::digests};
+use sui_sdk::{
+    rpc_types::{SuiCoinMetadata, SuiEvent},
+    types::{
+        base_types::{ObjectID, SuiAddress},
+        event::EventID,
+    },
+};
 
-use crate::utils::convert_chart_timestamp;
+use crate::{
+    error::{parse_field, OrMissing, Result},
+    event::{MoveCreatePoolEvent, MoveLiquidityEvent, MoveSwapEvent},
+    utils::convert_chart_timestamp,
+};
 
 pub type CoinType = String;
 //토큰 기본 정보
@@ -21,6 +31,9 @@ pub struct Token {
     pub create_time: u64,
     pub recent_trade: Option<u64>,
     pub create_digest: String,
+    pub package_version: Option<u64>,
+    #[serde(default)]
+    pub lifecycle: Lifecycle,
 }
 impl Token {
     pub fn new(
@@ -29,6 +42,7 @@ impl Token {
         total_supply: u64,
         create_time: u64,
         create_digest: String,
+        package_version: Option<u64>,
     ) -> Self {
         Token {
             name: metadata.name,
@@ -41,10 +55,15 @@ impl Token {
             create_time,
             recent_trade: None,
             create_digest,
+            package_version,
+            lifecycle: Lifecycle::Launching,
         }
     }
+    /// 늦게 처리된 이전 거래로 최근 거래 시각을 되돌리지 않습니다.
     pub fn update_recent_trade(&mut self, timestamp: u64) {
-        self.recent_trade = Some(timestamp);
+        if self.recent_trade.map_or(true, |recent| recent < timestamp) {
+            self.recent_trade = Some(timestamp);
+        }
     }
 }
 
@@ -64,21 +83,23 @@ pub struct Swap {
     pub digest: String,
 }
 impl Swap {
-    pub fn new(event: SwapEvent) -> Self {
-        Swap {
-            account: event.account,
-            pool_id: event.pool_id,
-            meme_in_amount: event.meme_in_amount.parse().unwrap(),
-            meme_out_amount: event.meme_out_amount.parse().unwrap(),
-            sui_in_amount: event.sui_in_amount.parse().unwrap(),
-            sui_out_amount: event.sui_out_amount.parse().unwrap(),
-            reserve_meme: event.reserve_meme.parse().unwrap(),
-            reserve_sui: event.reserve_sui.parse().unwrap(),
-            timestamp: event.timestamp.unwrap(),
-            coin_type: event.coin_type.unwrap(),
-            account_meme_balance: event.account_meme_balance.unwrap(),
-            digest: event.digest.unwrap(),
-        }
+    pub fn new(event: SwapEvent) -> Result<Self> {
+        Ok(Swap {
+            meme_in_amount: event.meme_in_amount,
+            meme_out_amount: event.meme_out_amount,
+            sui_in_amount: event.sui_in_amount,
+            sui_out_amount: event.sui_out_amount,
+            reserve_meme: event.reserve_meme,
+            reserve_sui: event.reserve_sui,
+            timestamp: event.timestamp.or_missing("timestamp")?,
+            coin_type: event.coin_type.or_missing("coin_type")?,
+            account_meme_balance: event
+                .account_meme_balance
+                .or_missing("account_meme_balance")?,
+            digest: event.digest.or_missing("digest")?,
+            account: event.account.to_string(),
+            pool_id: event.pool_id.to_string(),
+        })
     }
 }
 //Account 정보
@@ -101,8 +122,24 @@ impl TradeData {
         TradeData { trades: vec![] }
     }
 
-    pub fn add_trade(&mut self, trade: Trade) {
-        self.trades.insert(0, trade);
+    pub fn contains(&self, transaction_hash: &str, event_seq: u64) -> bool {
+        self.trades
+            .iter()
+            .any(|trade| trade.transaction_hash == transaction_hash && trade.event_seq == event_seq)
+    }
+
+    /// 거래를 시각 순서(최신순) 자리에 넣습니다. 이미 저장된 이벤트면 넣지 않고 false를 반환합니다.
+    pub fn add_trade(&mut self, trade: Trade) -> bool {
+        if self.contains(&trade.transaction_hash, trade.event_seq) {
+            return false;
+        }
+        let index = self
+            .trades
+            .iter()
+            .position(|stored| stored.timestamp <= trade.timestamp)
+            .unwrap_or(self.trades.len());
+        self.trades.insert(index, trade);
+        true
     }
 }
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -125,30 +162,167 @@ pub struct Trade {
     pub timestamp: u64,
     #[serde(rename = "transactionHash")]
     pub transaction_hash: String,
+    /// 트랜잭션 안의 이벤트 순번. 트랜잭션 해시와 함께 같은 거래를 두 번 저장하지 않는 데 씁니다.
+    #[serde(rename = "eventSeq", default)]
+    pub event_seq: u64,
+    #[serde(rename = "packageVersion")]
+    pub package_version: Option<u64>,
+    /// 거래 직후 계정의 밈 코인 잔고
+    #[serde(rename = "accountMemeBalance", default)]
+    pub account_meme_balance: Option<u64>,
+    /// 외부 DEX에서 체결된 거래라면 DEX 이름
+    #[serde(rename = "venue")]
+    pub venue: Option<String>,
 }
 
 impl Trade {
-    pub fn new(event: SwapEvent) -> Self {
-        let trade_type = if event.sui_out_amount == "0" && event.meme_in_amount == "0" {
+    pub fn new(event: SwapEvent) -> Result<Self> {
+        let trade_type = if event.sui_out_amount == 0 && event.meme_in_amount == 0 {
             TradeType::Buy
         } else {
             TradeType::Sell
         };
-        match trade_type {
-            TradeType::Buy => Trade {
-                account: event.account,
-                trade_type,
-                sui_amount: event.sui_in_amount.parse().unwrap(),
-                timestamp: event.timestamp.unwrap(),
-                transaction_hash: event.digest.unwrap(),
-            },
-            TradeType::Sell => Trade {
-                account: event.account,
-                trade_type,
-                sui_amount: event.sui_out_amount.parse().unwrap(),
-                timestamp: event.timestamp.unwrap(),
-                transaction_hash: event.digest.unwrap(),
-            },
+        let sui_amount = match trade_type {
+            TradeType::Buy => event.sui_in_amount,
+            TradeType::Sell => event.sui_out_amount,
+        };
+        Ok(Trade {
+            account: event.account.to_string(),
+            trade_type,
+            sui_amount,
+            timestamp: event.timestamp.or_missing("timestamp")?,
+            transaction_hash: event.digest.or_missing("digest")?,
+            event_seq: event.event_seq.or_missing("event_seq")?,
+            package_version: event.package_version,
+            account_meme_balance: event.account_meme_balance,
+            venue: event.venue,
+        })
+    }
+}
+
+//유동성 공급/회수 기록
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum LiquidityActionType {
+    #[serde(rename = "add")]
+    Add,
+    #[serde(rename = "remove")]
+    Remove,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LiquidityAction {
+    #[serde(rename = "account")]
+    pub account: String,
+    #[serde(rename = "coinType")]
+    pub coin_type: CoinType,
+    #[serde(rename = "actionType")]
+    pub action_type: LiquidityActionType,
+    #[serde(rename = "memeAmount")]
+    pub meme_amount: u64,
+    #[serde(rename = "suiAmount")]
+    pub sui_amount: u64,
+    #[serde(rename = "lpAmount")]
+    pub lp_amount: u64,
+    #[serde(rename = "updatedTimeStampAt")]
+    pub timestamp: u64,
+    #[serde(rename = "transactionHash")]
+    pub transaction_hash: String,
+    #[serde(rename = "eventSeq")]
+    pub event_seq: u64,
+    #[serde(rename = "packageVersion")]
+    pub package_version: Option<u64>,
+}
+
+impl LiquidityAction {
+    pub fn new(
+        event: &MoveLiquidityEvent,
+        action_type: LiquidityActionType,
+        coin_type: CoinType,
+        timestamp: u64,
+        event_id: &EventID,
+        package_version: u64,
+    ) -> Self {
+        LiquidityAction {
+            account: event.account.to_string(),
+            coin_type,
+            action_type,
+            meme_amount: event.meme_amount,
+            sui_amount: event.sui_amount,
+            lp_amount: event.lp_amount,
+            timestamp,
+            transaction_hash: event_id.tx_digest.to_string(),
+            event_seq: event_id.event_seq,
+            package_version: Some(package_version),
+        }
+    }
+
+    /// 같은 이벤트를 다시 처리해도 한 번만 남도록 이벤트 ID를 키로 씁니다.
+    pub fn id(&self) -> String {
+        format!("{}_{}", self.transaction_hash, self.event_seq)
+    }
+}
+
+/// 계정의 밈 코인 잔고. 계정의 마지막 `BalanceChange`의 잔고입니다.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AccountBalance {
+    #[serde(rename = "account")]
+    pub account: String,
+    #[serde(rename = "coinType")]
+    pub coin_type: CoinType,
+    #[serde(rename = "balance")]
+    pub balance: u64,
+    /// 마지막으로 반영한 트랜잭션
+    #[serde(rename = "transactionHash")]
+    pub transaction_hash: String,
+}
+
+impl AccountBalance {
+    pub fn id(coin_type: &str, account: &str) -> String {
+        format!("{}_{}", coin_type, account)
+    }
+}
+
+/// 트랜잭션 하나가 계정 잔고에 반영한 변화
+///
+/// 계정의 기록은 (`timestamp`, `version`) 순서로 놓이고, 잔고는 0에서 시작해 그 순서대로 변화를 더한 값입니다.
+/// 같은 트랜잭션을 다시 처리하면 더하지 않고 기록된 잔고를 씁니다.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BalanceChange {
+    #[serde(rename = "account")]
+    pub account: String,
+    #[serde(rename = "coinType")]
+    pub coin_type: CoinType,
+    #[serde(rename = "transactionHash")]
+    pub transaction_hash: String,
+    #[serde(rename = "amount")]
+    pub amount: i64,
+    /// 이 트랜잭션까지의 잔고. 인덱싱하지 않은 전송으로 받은 코인을 내보내면 음수가 됩니다.
+    #[serde(rename = "balance")]
+    pub balance: i64,
+    /// 트랜잭션이 실린 체크포인트의 시각 (ms)
+    #[serde(rename = "timestamp", default)]
+    pub timestamp: u64,
+    /// 트랜잭션이 바꾼 객체의 버전. 같은 계정의 트랜잭션은 이 값이 늘어나므로 같은 시각 안의 순서로 씁니다.
+    #[serde(rename = "version", default)]
+    pub version: u64,
+}
+
+impl BalanceChange {
+    pub fn id(transaction_hash: &str, coin_type: &str, account: &str) -> String {
+        format!("{}_{}_{}", transaction_hash, coin_type, account)
+    }
+
+    /// 0 아래로 내려가지 않은 잔고
+    pub fn clamped_balance(&self) -> u64 {
+        u64::try_from(self.balance).unwrap_or(0)
+    }
+
+    pub fn account_balance(&self) -> AccountBalance {
+        AccountBalance {
+            account: self.account.clone(),
+            coin_type: self.coin_type.clone(),
+            balance: self.clamped_balance(),
+            transaction_hash: self.transaction_hash.clone(),
         }
     }
 }
@@ -161,17 +335,84 @@ pub struct PoolInfo {
     pub reserve_meme: u64,
     pub reserve_sui: u64,
     pub time_stamp: u64,
+    pub package_version: Option<u64>,
+    #[serde(default)]
+    pub lifecycle: Lifecycle,
+    /// reserve를 바꾼 마지막 이벤트의 시각. 이보다 이전 이벤트로는 reserve를 되돌리지 않습니다.
+    #[serde(default)]
+    pub reserves_updated_at: u64,
 }
 
 impl PoolInfo {
-    pub fn new(event: CreatePoolEvent) -> Self {
-        PoolInfo {
-            coin_type: event.coin_type.unwrap(),
-            pool_id: event.pool_id,
-            reserve_meme: event.reserve_meme.parse().unwrap(),
-            reserve_sui: event.reserve_sui.parse().unwrap(),
-            time_stamp: event.timestamp.unwrap(),
+    pub fn new(event: CreatePoolEvent) -> Result<Self> {
+        let timestamp = event.timestamp.or_missing("timestamp")?;
+        Ok(PoolInfo {
+            coin_type: event.coin_type.or_missing("coin_type")?,
+            pool_id: event.pool_id.to_string(),
+            reserve_meme: event.reserve_meme,
+            reserve_sui: event.reserve_sui,
+            time_stamp: timestamp,
+            package_version: event.package_version,
+            lifecycle: Lifecycle::Launching,
+            reserves_updated_at: timestamp,
+        })
+    }
+}
+
+//토큰/풀 상태
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(tag = "state")]
+pub enum Lifecycle {
+    /// 본딩 커브에서 거래 중
+    #[default]
+    #[serde(rename = "launching")]
+    Launching,
+    /// 본딩 커브를 다 채워 더 이상 이 AMM에서 거래되지 않음
+    #[serde(rename = "graduated")]
+    Graduated {
+        #[serde(rename = "graduatedAt")]
+        graduated_at: u64,
+    },
+    /// 외부 DEX 풀로 유동성이 옮겨짐
+    #[serde(rename = "migrated")]
+    Migrated {
+        venue: String,
+        #[serde(rename = "targetPoolId")]
+        target_pool_id: String,
+        #[serde(rename = "migratedAt")]
+        migrated_at: u64,
+    },
+}
+
+impl PoolInfo {
+    /// `timestamp` 시각의 이벤트가 알려 준 reserve를 반영합니다. 저장된 값보다 이전 이벤트면 false를 반환합니다.
+    pub fn update_reserves(&mut self, reserve_meme: u64, reserve_sui: u64, timestamp: u64) -> bool {
+        if timestamp < self.reserves_updated_at {
+            return false;
+        }
+        self.reserve_meme = reserve_meme;
+        self.reserve_sui = reserve_sui;
+        self.reserves_updated_at = timestamp;
+        true
+    }
+}
+
+impl Lifecycle {
+    fn stage(&self) -> u8 {
+        match self {
+            Lifecycle::Launching => 0,
+            Lifecycle::Graduated { .. } => 1,
+            Lifecycle::Migrated { .. } => 2,
+        }
+    }
+
+    /// 다음 단계로만 바꿉니다. 재처리 등으로 이전 단계 이벤트가 늦게 와도 되돌리지 않습니다.
+    pub fn advance(&mut self, next: Lifecycle) -> bool {
+        if next.stage() <= self.stage() {
+            return false;
         }
+        *self = next;
+        true
     }
 }
 
@@ -184,15 +425,113 @@ impl ChartData {
         self.charts.insert(0, chart);
     }
 
-    pub fn update_latest_chart(&mut self, timestamp: u64, price: Decimal) {
-        if let Some(latest_chart) = self.charts.first_mut() {
-            if latest_chart.chart_timestamp == convert_chart_timestamp(timestamp) {
-                latest_chart.update(price);
-            } else {
-                self.add_chart(Chart::new(timestamp, price));
+    /// 거래를 해당 구간 캔들에 반영하고 그 캔들을 반환합니다.
+    ///
+    /// 재시도 등으로 늦게 처리된 거래도 시각에 맞는 구간에 반영하며, 구간 캔들이 없으면 순서에 맞는 자리에 만듭니다.
+    pub fn apply_trade(&mut self, timestamp: u64, price: Decimal) -> Result<Chart> {
+        let index = self.bucket_index(timestamp);
+        match self.charts.get_mut(index) {
+            Some(chart) if chart.chart_timestamp == convert_chart_timestamp(timestamp) => {
+                chart.update(timestamp, price)?
+            }
+            _ => self.charts.insert(index, Chart::new(timestamp, price)),
+        }
+        Ok(self.charts[index].clone())
+    }
+
+    /// 가격은 그대로 두고 해당 구간 캔들에 유동성 변화를 표시하고 그 캔들을 반환합니다.
+    ///
+    /// 구간 캔들이 없으면 직전 구간의 종가로, 직전 구간이 없으면 주어진 가격으로 캔들을 만듭니다.
+    pub fn mark_liquidity_change(&mut self, timestamp: u64, price: Decimal) -> Result<Chart> {
+        let index = self.bucket_index(timestamp);
+        match self.charts.get_mut(index) {
+            Some(chart) if chart.chart_timestamp == convert_chart_timestamp(timestamp) => {
+                chart.liquidity_changes += 1;
+            }
+            _ => {
+                let price = match self.charts.get(index) {
+                    Some(previous) => parse_field(&previous.close_price, "close_price")?,
+                    None => price,
+                };
+                let mut chart = Chart::new(timestamp, price);
+                // 거래 없이 만든 캔들의 종가는 이 구간의 어떤 거래로도 바뀌어야 합니다.
+                chart.last_trade_at = 0;
+                chart.liquidity_changes = 1;
+                self.charts.insert(index, chart);
             }
-        } else {
-            self.add_chart(Chart::new(timestamp, price));
+        }
+        Ok(self.charts[index].clone())
+    }
+
+    /// 최신순으로 정렬된 캔들에서 `timestamp`가 속한 구간의 캔들이나 그 구간을 넣을 자리
+    fn bucket_index(&self, timestamp: u64) -> usize {
+        let chart_timestamp = convert_chart_timestamp(timestamp);
+        self.charts
+            .iter()
+            .position(|chart| chart.chart_timestamp <= chart_timestamp)
+            .unwrap_or(self.charts.len())
+    }
+
+    /// 5분 캔들을 주어진 해상도로 묶어 최신순으로 반환합니다.
+    pub fn candles(&self, resolution: Resolution) -> Result<Vec<Chart>> {
+        let step = resolution.seconds();
+        let mut candles: Vec<Chart> = Vec::new();
+
+        for chart in self.charts.iter().rev() {
+            let bucket = (chart.chart_timestamp + step - 1) / step * step;
+            match candles.last_mut() {
+                Some(candle) if candle.chart_timestamp == bucket => candle.merge(chart)?,
+                _ => {
+                    let mut candle = chart.clone();
+                    candle.chart_timestamp = bucket;
+                    candles.push(candle);
+                }
+            }
+        }
+
+        candles.reverse();
+        Ok(candles)
+    }
+}
+
+//차트 해상도
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Resolution {
+    #[serde(rename = "5m")]
+    FiveMinutes,
+    #[serde(rename = "15m")]
+    FifteenMinutes,
+    #[serde(rename = "1h")]
+    OneHour,
+    #[serde(rename = "4h")]
+    FourHours,
+    #[serde(rename = "1d")]
+    OneDay,
+}
+
+impl Resolution {
+    pub fn seconds(&self) -> u64 {
+        match self {
+            Resolution::FiveMinutes => 5 * 60,
+            Resolution::FifteenMinutes => 15 * 60,
+            Resolution::OneHour => 60 * 60,
+            Resolution::FourHours => 4 * 60 * 60,
+            Resolution::OneDay => 24 * 60 * 60,
+        }
+    }
+}
+
+impl FromStr for Resolution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "5m" => Ok(Resolution::FiveMinutes),
+            "15m" => Ok(Resolution::FifteenMinutes),
+            "1h" => Ok(Resolution::OneHour),
+            "4h" => Ok(Resolution::FourHours),
+            "1d" => Ok(Resolution::OneDay),
+            _ => Err(format!("Invalid resolution: {}", s)),
         }
     }
 }
@@ -210,6 +549,14 @@ pub struct Chart {
     #[serde(rename = "openPrice")]
     pub open_price: String,
     pub close_price: String,
+    /// 이 구간에 있었던 유동성 공급/회수 횟수
+    #[serde(rename = "liquidityChanges", default)]
+    pub liquidity_changes: u32,
+    /// 시가와 종가를 정한 거래의 시각. 늦게 처리된 거래가 순서를 뒤바꾸지 않도록 비교합니다.
+    #[serde(rename = "firstTradeAt", default)]
+    pub first_trade_at: u64,
+    #[serde(rename = "lastTradeAt", default)]
+    pub last_trade_at: u64,
 }
 
 impl Chart {
@@ -222,51 +569,270 @@ impl Chart {
             current_price: current_price.clone(),
             open_price: current_price.clone(),
             close_price: current_price,
+            liquidity_changes: 0,
+            first_trade_at: timestamp,
+            last_trade_at: timestamp,
         }
     }
-    pub fn update(&mut self, current_price: Decimal) {
+    pub fn update(&mut self, timestamp: u64, current_price: Decimal) -> Result<()> {
         let current_price_str = current_price.to_string();
 
-        if current_price > Decimal::from_str(&self.high_price).unwrap() {
+        if timestamp < self.first_trade_at {
+            self.open_price = current_price_str.clone();
+            self.first_trade_at = timestamp;
+        }
+
+        if current_price > parse_field::<Decimal>(&self.high_price, "high_price")? {
             self.high_price = current_price_str.clone();
         }
 
-        if current_price < Decimal::from_str(&self.low_price).unwrap() {
+        if current_price < parse_field::<Decimal>(&self.low_price, "low_price")? {
             self.low_price = current_price_str.clone();
         }
 
-        self.current_price = current_price_str.clone();
-        self.close_price = current_price_str;
+        if timestamp >= self.last_trade_at {
+            self.current_price = current_price_str.clone();
+            self.close_price = current_price_str;
+            self.last_trade_at = timestamp;
+        }
+        Ok(())
+    }
+
+    /// 뒤따르는 캔들을 현재 캔들에 합칩니다.
+    pub fn merge(&mut self, next: &Chart) -> Result<()> {
+        if parse_field::<Decimal>(&next.high_price, "high_price")?
+            > parse_field::<Decimal>(&self.high_price, "high_price")?
+        {
+            self.high_price = next.high_price.clone();
+        }
+
+        if parse_field::<Decimal>(&next.low_price, "low_price")?
+            < parse_field::<Decimal>(&self.low_price, "low_price")?
+        {
+            self.low_price = next.low_price.clone();
+        }
+
+        self.current_price = next.current_price.clone();
+        self.close_price = next.close_price.clone();
+        self.liquidity_changes += next.liquidity_changes;
+        self.last_trade_at = next.last_trade_at;
+        Ok(())
+    }
+}
+
+//실시간 업데이트
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum MarketUpdate {
+    #[serde(rename = "trade")]
+    Trade {
+        #[serde(rename = "coinType")]
+        coin_type: CoinType,
+        trade: Trade,
+    },
+    #[serde(rename = "chart")]
+    Chart {
+        #[serde(rename = "coinType")]
+        coin_type: CoinType,
+        chart: Chart,
+    },
+    #[serde(rename = "poolInfo")]
+    PoolInfo {
+        #[serde(rename = "coinType")]
+        coin_type: CoinType,
+        #[serde(rename = "poolInfo")]
+        pool_info: PoolInfo,
+    },
+    #[serde(rename = "launch")]
+    Launch {
+        #[serde(rename = "coinType")]
+        coin_type: CoinType,
+        token: Token,
+        #[serde(rename = "poolInfo")]
+        pool_info: PoolInfo,
+    },
+    #[serde(rename = "liquidity")]
+    Liquidity {
+        #[serde(rename = "coinType")]
+        coin_type: CoinType,
+        action: LiquidityAction,
+    },
+    #[serde(rename = "lifecycle")]
+    Lifecycle {
+        #[serde(rename = "coinType")]
+        coin_type: CoinType,
+        lifecycle: Lifecycle,
+    },
+}
+
+impl MarketUpdate {
+    pub fn coin_type(&self) -> &str {
+        match self {
+            MarketUpdate::Trade { coin_type, .. }
+            | MarketUpdate::Chart { coin_type, .. }
+            | MarketUpdate::PoolInfo { coin_type, .. }
+            | MarketUpdate::Launch { coin_type, .. }
+            | MarketUpdate::Liquidity { coin_type, .. }
+            | MarketUpdate::Lifecycle { coin_type, .. } => coin_type,
+        }
+    }
+}
+
+//이벤트 구독 위치
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventCursor {
+    pub package_id: String,
+    pub tx_digest: String,
+    pub event_seq: u64,
+}
+
+impl EventCursor {
+    pub fn new(package_id: &ObjectID, event_id: &EventID) -> Self {
+        EventCursor {
+            package_id: package_id.to_string(),
+            tx_digest: event_id.tx_digest.to_string(),
+            event_seq: event_id.event_seq,
+        }
+    }
+
+    pub fn event_id(&self) -> Result<EventID> {
+        Ok(EventID {
+            tx_digest: parse_field(&self.tx_digest, "tx_digest")?,
+            event_seq: self.event_seq,
+        })
+    }
+}
+
+//처리에 계속 실패한 이벤트
+/// 재시도를 기다리는 이벤트도 같은 형태로 `PENDING_RETRY`에 저장합니다.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub package_id: String,
+    pub event_name: String,
+    pub tx_digest: String,
+    pub event_seq: u64,
+    pub package_version: u64,
+    /// 원본 SuiEvent (JSON)
+    pub event: String,
+    pub attempts: u32,
+    pub last_error: String,
+    pub failed_at: u64,
+}
+
+impl DeadLetter {
+    pub fn new(
+        event: &SuiEvent,
+        package_version: u64,
+        attempts: u32,
+        last_error: String,
+    ) -> serde_json::Result<Self> {
+        Ok(DeadLetter {
+            package_id: event.package_id.to_string(),
+            event_name: event.type_.name.to_string(),
+            tx_digest: event.id.tx_digest.to_string(),
+            event_seq: event.id.event_seq,
+            package_version,
+            event: serde_json::to_string(event)?,
+            attempts,
+            last_error,
+            failed_at: Utc::now().timestamp_millis() as u64,
+        })
+    }
+
+    pub fn id(&self) -> String {
+        format!("{}_{}", self.tx_digest, self.event_seq)
+    }
+
+    pub fn id_of(event_id: &EventID) -> String {
+        format!("{}_{}", event_id.tx_digest, event_id.event_seq)
+    }
+
+    pub fn event(&self) -> serde_json::Result<SuiEvent> {
+        serde_json::from_str(&self.event)
+    }
+
+    /// 재처리에 다시 실패한 경우 기록을 갱신합니다.
+    pub fn failed_again(&mut self, last_error: String) {
+        self.attempts += 1;
+        self.last_error = last_error;
+        self.failed_at = Utc::now().timestamp_millis() as u64;
     }
 }
 
 //Event
+/// 체인에서 디코딩한 스왑 이벤트에 처리 중 조회한 값을 더한 것
+///
+/// 외부 DEX 스왑도 같은 형태로 정규화합니다. 이때 reserve는 0이고 venue에 DEX 이름이 들어갑니다.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SwapEvent {
-    pub account: String,
-    pub pool_id: String,
-    pub meme_in_amount: String,
-    pub meme_out_amount: String,
-    pub sui_in_amount: String,
-    pub sui_out_amount: String,
-    pub reserve_meme: String,
-    pub reserve_sui: String,
+    pub account: SuiAddress,
+    pub pool_id: ObjectID,
+    pub meme_in_amount: u64,
+    pub meme_out_amount: u64,
+    pub sui_in_amount: u64,
+    pub sui_out_amount: u64,
+    pub reserve_meme: u64,
+    pub reserve_sui: u64,
     pub timestamp: Option<u64>,
     pub coin_type: Option<String>,
     pub account_meme_balance: Option<u64>,
     pub digest: Option<String>,
+    pub event_seq: Option<u64>,
     pub current_price: Option<Decimal>,
+    pub package_version: Option<u64>,
+    pub venue: Option<String>,
+}
+
+impl SwapEvent {
+    pub fn new(event: MoveSwapEvent) -> Self {
+        SwapEvent {
+            account: event.account,
+            pool_id: event.pool_id,
+            meme_in_amount: event.meme_in_amount,
+            meme_out_amount: event.meme_out_amount,
+            sui_in_amount: event.sui_in_amount,
+            sui_out_amount: event.sui_out_amount,
+            reserve_meme: event.reserve_meme,
+            reserve_sui: event.reserve_sui,
+            timestamp: None,
+            coin_type: None,
+            account_meme_balance: None,
+            digest: None,
+            event_seq: None,
+            current_price: None,
+            package_version: None,
+            venue: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreatePoolEvent {
     pub coin_type: Option<CoinType>,
-    pub metadata_id: String,
-    pub pool_id: String,
-    pub reserve_meme: String,
-    pub reserve_sui: String,
-    pub account: String,
-    pub treasury_id: String,
+    pub metadata_id: ObjectID,
+    pub pool_id: ObjectID,
+    pub reserve_meme: u64,
+    pub reserve_sui: u64,
+    pub account: SuiAddress,
+    pub treasury_id: ObjectID,
     pub timestamp: Option<u64>,
     pub digest: Option<String>,
+    pub package_version: Option<u64>,
+}
+
+impl CreatePoolEvent {
+    pub fn new(event: MoveCreatePoolEvent) -> Self {
+        CreatePoolEvent {
+            coin_type: None,
+            metadata_id: event.metadata_id,
+            pool_id: event.pool_id,
+            reserve_meme: event.reserve_meme,
+            reserve_sui: event.reserve_sui,
+            account: event.account,
+            treasury_id: event.treasury_id,
+            timestamp: None,
+            digest: None,
+            package_version: None,
+        }
+    }
 }
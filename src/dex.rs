@@ -0,0 +1,291 @@
+use std::fmt;
+
+use serde::Deserialize;
+use sui_move_types::{account_address::AccountAddress, language_storage::StructTag};
+use sui_sdk::{
+    rpc_types::{EventFilter, SuiEvent},
+    types::{
+        base_types::{ObjectID, SuiAddress},
+        Identifier,
+    },
+};
+
+use crate::{
+    error::Result,
+    event::{decode, move_number, MoveEvent},
+};
+
+/// 졸업한 토큰이 거래되는 외부 DEX
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Venue {
+    Cetus,
+    Turbos,
+    DeepBook,
+}
+
+impl Venue {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Venue::Cetus => "cetus",
+            Venue::Turbos => "turbos",
+            Venue::DeepBook => "deepbook",
+        }
+    }
+
+    /// 스왑 이벤트 타입
+    pub fn event_type(&self, package_id: ObjectID) -> anyhow::Result<StructTag> {
+        let (module, name) = match self {
+            Venue::Cetus => (CetusSwapEvent::MODULE, CetusSwapEvent::NAME),
+            Venue::Turbos => (TurbosSwapEvent::MODULE, TurbosSwapEvent::NAME),
+            Venue::DeepBook => (DeepBookOrderFilled::MODULE, DeepBookOrderFilled::NAME),
+        };
+        Ok(StructTag {
+            address: AccountAddress::from(package_id),
+            module: Identifier::new(module.unwrap_or_default())?,
+            name: Identifier::new(name)?,
+            type_params: vec![],
+        })
+    }
+
+    /// 구독 필터. 세 DEX의 스왑 이벤트 모두 제네릭이 아니므로 타입으로 구독합니다.
+    pub fn filter(&self, package_id: ObjectID) -> anyhow::Result<EventFilter> {
+        Ok(EventFilter::MoveEventType(self.event_type(package_id)?))
+    }
+}
+
+impl fmt::Display for Venue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// DEX마다 다른 스왑 이벤트를 풀의 두 코인(a, b) 기준으로 정규화한 것
+///
+/// (a, b) 코인 타입은 이벤트에 없으므로 풀 객체에서 조회합니다.
+#[derive(Debug, Clone)]
+pub struct DexSwap {
+    pub venue: Venue,
+    pub pool_id: ObjectID,
+    pub account: SuiAddress,
+    /// a를 내고 b를 받았는지
+    pub a_to_b: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
+impl DexSwap {
+    pub fn parse(venue: Venue, event: &SuiEvent) -> Result<Self> {
+        match venue {
+            Venue::Cetus => {
+                let swap = decode::<CetusSwapEvent>(event)?;
+                Ok(DexSwap {
+                    venue,
+                    pool_id: swap.pool,
+                    account: event.sender,
+                    a_to_b: swap.atob,
+                    amount_in: swap.amount_in,
+                    amount_out: swap.amount_out,
+                })
+            }
+            Venue::Turbos => {
+                let swap = decode::<TurbosSwapEvent>(event)?;
+                let (amount_in, amount_out) = if swap.a_to_b {
+                    (swap.amount_a, swap.amount_b)
+                } else {
+                    (swap.amount_b, swap.amount_a)
+                };
+                Ok(DexSwap {
+                    venue,
+                    pool_id: swap.pool,
+                    account: swap.recipient,
+                    a_to_b: swap.a_to_b,
+                    amount_in,
+                    amount_out,
+                })
+            }
+            Venue::DeepBook => {
+                let fill = decode::<DeepBookOrderFilled>(event)?;
+                // taker가 매수했으면 quote(b)를 내고 base(a)를 받았습니다.
+                let (amount_in, amount_out) = if fill.taker_is_bid {
+                    (fill.quote_quantity, fill.base_quantity)
+                } else {
+                    (fill.base_quantity, fill.quote_quantity)
+                };
+                // 이벤트에는 BalanceManager id만 있으므로 거래한 계정은 트랜잭션 발신자로 봅니다.
+                Ok(DexSwap {
+                    venue,
+                    pool_id: fill.pool_id,
+                    account: event.sender,
+                    a_to_b: !fill.taker_is_bid,
+                    amount_in,
+                    amount_out,
+                })
+            }
+        }
+    }
+}
+
+/// Cetus CLMM `pool::SwapEvent`
+///
+/// 세 DEX 모두 `tests/fixtures/dex`는 아래 정의로 만든 것이라 체인의 이벤트로 확인하지 못했으므로,
+/// 처리할 때 `parsed_json`의 필드 이름이 맞아야 합니다.
+///
+/// ```move
+/// struct SwapEvent has copy, drop, store {
+///     atob: bool,
+///     pool: ID,
+///     partner: ID,
+///     amount_in: u64,
+///     amount_out: u64,
+///     ref_amount: u64,
+///     fee_amount: u64,
+///     vault_a_amount: u64,
+///     vault_b_amount: u64,
+///     before_sqrt_price: u128,
+///     after_sqrt_price: u128,
+///     steps: u64,
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CetusSwapEvent {
+    pub atob: bool,
+    pub pool: ObjectID,
+    pub partner: ObjectID,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub amount_in: u64,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub amount_out: u64,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub ref_amount: u64,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub fee_amount: u64,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub vault_a_amount: u64,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub vault_b_amount: u64,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub before_sqrt_price: u128,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub after_sqrt_price: u128,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub steps: u64,
+}
+
+impl MoveEvent for CetusSwapEvent {
+    const NAME: &'static str = "SwapEvent";
+    const MODULE: Option<&'static str> = Some("pool");
+    const LAYOUT_VERIFIED: bool = false;
+}
+
+/// Turbos `i32::I32`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TurbosI32 {
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub bits: u32,
+}
+
+/// Turbos CLMM `pool::SwapEvent`
+///
+/// ```move
+/// struct SwapEvent has copy, drop {
+///     pool: ID,
+///     recipient: address,
+///     amount_a: u64,
+///     amount_b: u64,
+///     liquidity: u128,
+///     tick_current_index: I32,
+///     tick_pre_index: I32,
+///     sqrt_price: u128,
+///     protocol_fee: u64,
+///     fee_amount: u64,
+///     a_to_b: bool,
+///     is_exact_in: bool,
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TurbosSwapEvent {
+    pub pool: ObjectID,
+    pub recipient: SuiAddress,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub amount_a: u64,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub amount_b: u64,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub liquidity: u128,
+    pub tick_current_index: TurbosI32,
+    pub tick_pre_index: TurbosI32,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub sqrt_price: u128,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub protocol_fee: u64,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub fee_amount: u64,
+    pub a_to_b: bool,
+    pub is_exact_in: bool,
+}
+
+impl MoveEvent for TurbosSwapEvent {
+    const NAME: &'static str = "SwapEvent";
+    const MODULE: Option<&'static str> = Some("pool");
+    const LAYOUT_VERIFIED: bool = false;
+}
+
+/// DeepBook v3 `order_info::OrderFilled`
+///
+/// 풀의 (base, quote) 코인은 이벤트 타입에 없으므로 풀 객체에서 조회합니다.
+///
+/// ```move
+/// public struct OrderFilled has copy, store, drop {
+///     pool_id: ID,
+///     maker_order_id: u128,
+///     taker_order_id: u128,
+///     maker_client_order_id: u64,
+///     taker_client_order_id: u64,
+///     price: u64,
+///     taker_is_bid: bool,
+///     taker_fee: u64,
+///     taker_fee_is_deep: bool,
+///     maker_fee: u64,
+///     maker_fee_is_deep: bool,
+///     base_quantity: u64,
+///     quote_quantity: u64,
+///     maker_balance_manager_id: ID,
+///     taker_balance_manager_id: ID,
+///     timestamp: u64,
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DeepBookOrderFilled {
+    pub pool_id: ObjectID,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub maker_order_id: u128,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub taker_order_id: u128,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub maker_client_order_id: u64,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub taker_client_order_id: u64,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub price: u64,
+    pub taker_is_bid: bool,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub taker_fee: u64,
+    pub taker_fee_is_deep: bool,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub maker_fee: u64,
+    pub maker_fee_is_deep: bool,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub base_quantity: u64,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub quote_quantity: u64,
+    pub maker_balance_manager_id: ObjectID,
+    pub taker_balance_manager_id: ObjectID,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub timestamp: u64,
+}
+
+impl MoveEvent for DeepBookOrderFilled {
+    const NAME: &'static str = "OrderFilled";
+    const MODULE: Option<&'static str> = Some("order_info");
+    const LAYOUT_VERIFIED: bool = false;
+}
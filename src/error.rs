@@ -0,0 +1,67 @@
+use std::fmt::Display;
+
+use thiserror::Error;
+
+/// 이벤트 처리 파이프라인의 오류
+#[derive(Debug, Error)]
+pub enum Error {
+    /// 이벤트나 저장된 값을 해석하지 못했습니다. 다시 시도해도 같은 결과가 나옵니다.
+    #[error("failed to parse {field}: {message}")]
+    Parse {
+        field: &'static str,
+        message: String,
+    },
+    /// 이벤트나 온체인 객체에 필요한 값이 없습니다.
+    #[error("missing {0}")]
+    Missing(&'static str),
+    #[error("Sui RPC error: {0}")]
+    Rpc(#[from] sui_sdk::error::Error),
+    #[error("database error: {0}")]
+    Db(#[from] surrealdb::Error),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    pub fn parse(field: &'static str, error: impl Display) -> Self {
+        Error::Parse {
+            field,
+            message: error.to_string(),
+        }
+    }
+
+    /// 잠시 후 다시 시도하면 성공할 수 있는 오류인지 반환합니다.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Error::Rpc(_) | Error::Db(_))
+    }
+
+    /// 메트릭과 로그에 쓰는 오류 종류
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::Parse { .. } => "parse",
+            Error::Missing(_) => "missing",
+            Error::Rpc(_) => "rpc",
+            Error::Db(_) => "db",
+        }
+    }
+}
+
+/// 값이 없으면 `Error::Missing`으로 바꿉니다.
+pub trait OrMissing<T> {
+    fn or_missing(self, what: &'static str) -> Result<T>;
+}
+
+impl<T> OrMissing<T> for Option<T> {
+    fn or_missing(self, what: &'static str) -> Result<T> {
+        self.ok_or(Error::Missing(what))
+    }
+}
+
+/// 문자열 필드를 파싱하고 실패하면 필드 이름과 함께 `Error::Parse`로 바꿉니다.
+pub fn parse_field<T>(value: &str, field: &'static str) -> Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: Display,
+{
+    value.parse().map_err(|e| Error::parse(field, e))
+}
@@ -0,0 +1,230 @@
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+};
+
+use serde::{
+    de::{DeserializeOwned, Error as _},
+    Deserialize, Deserializer,
+};
+use sui_sdk::{
+    rpc_types::SuiEvent,
+    types::base_types::{ObjectID, SuiAddress},
+};
+use tracing::warn;
+
+use crate::{
+    error::{Error, Result},
+    metrics,
+};
+
+/// 체인에서 발생한 Move 이벤트
+///
+/// BCS는 필드 이름 없이 순서대로 인코딩되므로 필드 순서가 Move 구조체와 같아야 합니다.
+/// 노드가 필드 이름으로 풀어 준 `parsed_json`으로도 읽을 수 있어야 하므로 숫자 필드는
+/// `move_number`로 받습니다.
+pub trait MoveEvent: DeserializeOwned + PartialEq + Debug {
+    /// Move 구조체 이름
+    const NAME: &'static str;
+    /// 구조체를 정의한 모듈. AMM 모듈 이름은 설정으로 바뀌므로 None이면 확인하지 않습니다.
+    const MODULE: Option<&'static str> = None;
+    /// 체인에서 기록한 이벤트로 레이아웃을 확인했는지. 아니면 `parsed_json`으로 읽지 못할 때 실패합니다.
+    const LAYOUT_VERIFIED: bool = true;
+}
+
+/// Move의 u64/u128은 `parsed_json`에서 문자열로 오므로 사람이 읽는 형식에서는 문자열도 받습니다.
+pub mod move_number {
+    use super::*;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> std::result::Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de> + FromStr,
+        T::Err: Display,
+    {
+        if !deserializer.is_human_readable() {
+            return T::deserialize(deserializer);
+        }
+        match serde_json::Value::deserialize(deserializer)? {
+            serde_json::Value::String(value) => value.parse().map_err(D::Error::custom),
+            serde_json::Value::Number(value) => value.to_string().parse().map_err(D::Error::custom),
+            value => Err(D::Error::custom(format!(
+                "expected a number, got {}",
+                value
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MoveSwapEvent {
+    pub account: SuiAddress,
+    pub pool_id: ObjectID,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub meme_in_amount: u64,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub meme_out_amount: u64,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub sui_in_amount: u64,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub sui_out_amount: u64,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub reserve_meme: u64,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub reserve_sui: u64,
+}
+
+impl MoveEvent for MoveSwapEvent {
+    const NAME: &'static str = "SwapEvent";
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MoveCreatePoolEvent {
+    pub metadata_id: ObjectID,
+    pub pool_id: ObjectID,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub reserve_meme: u64,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub reserve_sui: u64,
+    pub account: SuiAddress,
+    pub treasury_id: ObjectID,
+}
+
+impl MoveEvent for MoveCreatePoolEvent {
+    const NAME: &'static str = "CreatePoolEvent";
+}
+
+/// 유동성 공급/회수 이벤트의 공통 필드
+///
+/// AMM `amm` 모듈의 두 이벤트는 필드가 같다고 보고 아래 정의를 따릅니다. 체인에서 기록한 이벤트로
+/// 확인하지 못했으므로(`tests/fixtures/amm`은 이 정의로 만든 것) 기본으로는 처리하지 않고, 처리할 때도
+/// `parsed_json`의 필드 이름이 맞아야 합니다.
+///
+/// ```move
+/// public struct AddLiquidityEvent has copy, drop {
+///     account: address,
+///     pool_id: ID,
+///     meme_amount: u64,
+///     sui_amount: u64,
+///     lp_amount: u64,
+///     reserve_meme: u64,
+///     reserve_sui: u64,
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MoveLiquidityEvent {
+    pub account: SuiAddress,
+    pub pool_id: ObjectID,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub meme_amount: u64,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub sui_amount: u64,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub lp_amount: u64,
+    /// 변경 후 reserve
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub reserve_meme: u64,
+    #[serde(deserialize_with = "move_number::deserialize")]
+    pub reserve_sui: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MoveAddLiquidityEvent(pub MoveLiquidityEvent);
+
+impl MoveEvent for MoveAddLiquidityEvent {
+    const NAME: &'static str = "AddLiquidityEvent";
+    const LAYOUT_VERIFIED: bool = false;
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MoveRemoveLiquidityEvent(pub MoveLiquidityEvent);
+
+impl MoveEvent for MoveRemoveLiquidityEvent {
+    const NAME: &'static str = "RemoveLiquidityEvent";
+    const LAYOUT_VERIFIED: bool = false;
+}
+
+/// 본딩 커브가 다 차서 풀이 더 이상 거래를 받지 않습니다.
+///
+/// 아래 정의는 체인에서 기록한 이벤트로 확인하지 못했으므로 기본으로는 처리하지 않습니다.
+///
+/// ```move
+/// public struct GraduateEvent has copy, drop {
+///     pool_id: ID,
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MoveGraduateEvent {
+    pub pool_id: ObjectID,
+}
+
+impl MoveEvent for MoveGraduateEvent {
+    const NAME: &'static str = "GraduateEvent";
+    const LAYOUT_VERIFIED: bool = false;
+}
+
+/// 졸업한 풀의 유동성이 외부 DEX 풀로 옮겨졌습니다.
+///
+/// 이후 거래는 `target_pool_id` 풀의 스왑 이벤트로 받습니다. 아래 정의는 체인에서 기록한 이벤트로
+/// 확인하지 못했으므로 기본으로는 처리하지 않습니다.
+///
+/// ```move
+/// public struct MigratePoolEvent has copy, drop {
+///     pool_id: ID,
+///     venue: String,
+///     target_pool_id: ID,
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MoveMigratePoolEvent {
+    pub pool_id: ObjectID,
+    /// 옮겨간 DEX 이름 (예: "cetus")
+    pub venue: String,
+    pub target_pool_id: ObjectID,
+}
+
+impl MoveEvent for MoveMigratePoolEvent {
+    const NAME: &'static str = "MigratePoolEvent";
+    const LAYOUT_VERIFIED: bool = false;
+}
+
+/// 이벤트의 BCS를 디코딩합니다.
+///
+/// 이벤트 타입이 `T`인지 먼저 확인하고, 디코딩한 값을 `parsed_json`과 비교합니다. 필드 이름으로
+/// 풀어 준 `parsed_json`이 기준이므로 다르면(구조체 필드 순서가 Move와 어긋나면) `parsed_json`의
+/// 값을 쓰고 `gmi_event_layout_mismatches_total`을 올립니다. 레이아웃을 확인하지 못한 이벤트는
+/// `parsed_json`으로 읽을 수 있어야 합니다.
+pub fn decode<T: MoveEvent>(event: &SuiEvent) -> Result<T> {
+    let type_ = &event.type_;
+    if type_.name.as_str() != T::NAME
+        || T::MODULE.map_or(false, |module| type_.module.as_str() != module)
+    {
+        return Err(Error::parse(
+            T::NAME,
+            format!("unexpected event type {}", type_),
+        ));
+    }
+    let decoded = bcs::from_bytes::<T>(&event.bcs).map_err(|e| Error::parse(T::NAME, e));
+    let parsed = serde_json::from_value::<T>(event.parsed_json.clone());
+    match (decoded, parsed) {
+        (Ok(decoded), Ok(parsed)) if decoded == parsed => Ok(decoded),
+        (Ok(_), Err(e)) if !T::LAYOUT_VERIFIED => Err(Error::parse(T::NAME, e)),
+        (Ok(decoded), Err(e)) => {
+            warn!(event_type = %type_, error = %e, "Failed to read parsed_json of event");
+            Ok(decoded)
+        }
+        (decoded, Ok(parsed)) => {
+            metrics::EVENT_LAYOUT_MISMATCHES
+                .with_label_values(&[T::NAME])
+                .inc();
+            warn!(
+                event_type = %type_,
+                tx_digest = %event.id.tx_digest,
+                bcs = ?decoded,
+                "BCS layout differs from parsed_json, using parsed_json"
+            );
+            Ok(parsed)
+        }
+        (Err(e), Err(_)) => Err(e),
+    }
+}
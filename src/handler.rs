@@ -0,0 +1,266 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use sui_move_types::{
+    account_address::AccountAddress,
+    language_storage::{StructTag, TypeTag},
+};
+use sui_sdk::{
+    rpc_types::SuiEvent,
+    types::{base_types::ObjectID, Identifier},
+    SuiClient,
+};
+use tokio::sync::{broadcast::Sender, Mutex};
+use tracing::{debug, warn};
+
+use crate::{
+    config::{DexConfig, PackageConfig},
+    db::{
+        model::{Lifecycle, LiquidityActionType, MarketUpdate},
+        Database,
+    },
+    dex::{DexSwap, Venue},
+    error::{Error, OrMissing},
+    event::{
+        decode, MoveAddLiquidityEvent, MoveCreatePoolEvent, MoveEvent, MoveGraduateEvent,
+        MoveMigratePoolEvent, MoveRemoveLiquidityEvent, MoveSwapEvent,
+    },
+    observe::{
+        control_swap_event, create_pool_event, dex_swap_event, get_pool_coins, lifecycle_event,
+        liquidity_event, AccountLocks, CoinTypeCache, MigrationTargets,
+    },
+};
+
+/// 이벤트 처리에 필요한 공유 자원
+pub struct HandlerContext<'a> {
+    pub sui: Arc<SuiClient>,
+    pub db: Arc<Database>,
+    pub coin_types: &'a CoinTypeCache,
+    pub migration_targets: &'a MigrationTargets,
+    pub account_locks: &'a AccountLocks,
+    pub update_sender: &'a Sender<MarketUpdate>,
+    /// 이벤트를 발생시킨 AMM 패키지 버전. 외부 DEX 이벤트는 0입니다.
+    pub package_version: u64,
+}
+
+/// 한 종류의 Move 이벤트를 처리합니다.
+#[async_trait]
+pub trait EventHandler: Send + Sync {
+    async fn handle(&self, ctx: &HandlerContext<'_>, event: SuiEvent) -> Result<(), Error>;
+}
+
+/// 이벤트 타입(address::module::name)별 처리기
+///
+/// 이름이 같아도 다른 패키지나 모듈의 이벤트는 등록되지 않았으므로 처리하지 않습니다.
+/// AMM 처리기는 설정된 패키지 ID로 등록되며, 업그레이드된 패키지의 이벤트 타입은 원래 패키지 주소를
+/// 유지하므로 이벤트를 발생시킨 패키지로 찾습니다.
+#[derive(Default)]
+pub struct HandlerRegistry {
+    handlers: HashMap<StructTag, Arc<dyn EventHandler>>,
+    /// 설정된 AMM 패키지 ID
+    amm_packages: HashSet<ObjectID>,
+}
+
+impl HandlerRegistry {
+    /// 설정된 AMM 패키지마다 처리하도록 설정된 이벤트의 처리기를 등록합니다.
+    pub fn amm(packages: &PackageConfig) -> anyhow::Result<Self> {
+        let mut registry = HandlerRegistry {
+            amm_packages: packages
+                .amm_packages
+                .iter()
+                .map(|package| package.package_id)
+                .collect(),
+            ..Default::default()
+        };
+        registry.register_amm::<MoveCreatePoolEvent>(packages, Arc::new(CreatePoolHandler))?;
+        registry.register_amm::<MoveSwapEvent>(packages, Arc::new(SwapHandler))?;
+        registry.register_amm::<MoveAddLiquidityEvent>(
+            packages,
+            Arc::new(LiquidityHandler(LiquidityActionType::Add)),
+        )?;
+        registry.register_amm::<MoveRemoveLiquidityEvent>(
+            packages,
+            Arc::new(LiquidityHandler(LiquidityActionType::Remove)),
+        )?;
+        registry.register_amm::<MoveGraduateEvent>(packages, Arc::new(GraduateHandler))?;
+        registry.register_amm::<MoveMigratePoolEvent>(packages, Arc::new(MigratePoolHandler))?;
+        Ok(registry)
+    }
+
+    pub fn register(&mut self, event_type: StructTag, handler: Arc<dyn EventHandler>) {
+        debug!(event_type = %event_type, "Registering event handler");
+        if self.handlers.insert(event_type.clone(), handler).is_some() {
+            warn!(event_type = %event_type, "Replaced event handler");
+        }
+    }
+
+    /// 설정된 외부 DEX마다 스왑 이벤트 처리기를 등록합니다.
+    pub fn register_dex(&mut self, dex: &DexConfig) -> anyhow::Result<()> {
+        for venue in &dex.venues {
+            self.register(
+                venue.venue.event_type(venue.package_id)?,
+                Arc::new(DexSwapHandler::new(venue.venue)),
+            );
+        }
+        Ok(())
+    }
+
+    /// 이벤트의 처리기를 찾습니다.
+    ///
+    /// 설정된 AMM 패키지가 발생시킨 이벤트는 그 패키지로 등록된 처리기만 찾으므로, 패키지별 `events`
+    /// 설정은 이벤트 타입을 정의한 패키지가 아니라 발생시킨 패키지 기준으로 적용됩니다. 그 밖의 이벤트는
+    /// 타입을 정의한 (원래) 패키지로 찾습니다.
+    pub fn resolve(&self, event: &SuiEvent) -> Option<&Arc<dyn EventHandler>> {
+        if self.amm_packages.contains(&event.package_id) {
+            return self.handlers.get(&StructTag {
+                address: AccountAddress::from(event.package_id),
+                ..event.type_.clone()
+            });
+        }
+        self.get(&event.type_)
+    }
+
+    /// 제네릭 이벤트는 타입 인자 없이 등록된 처리기로도 찾습니다.
+    pub fn get(&self, event_type: &StructTag) -> Option<&Arc<dyn EventHandler>> {
+        self.handlers.get(event_type).or_else(|| {
+            if event_type.type_params.is_empty() {
+                return None;
+            }
+            self.handlers.get(&StructTag {
+                type_params: vec![],
+                ..event_type.clone()
+            })
+        })
+    }
+
+    fn register_amm<T: MoveEvent>(
+        &mut self,
+        packages: &PackageConfig,
+        handler: Arc<dyn EventHandler>,
+    ) -> anyhow::Result<()> {
+        for package in packages
+            .amm_packages
+            .iter()
+            .filter(|package| package.handles(T::NAME))
+        {
+            let event_type = StructTag {
+                address: AccountAddress::from(package.package_id),
+                module: packages.amm_module.clone(),
+                name: Identifier::new(T::NAME)?,
+                type_params: vec![],
+            };
+            self.register(event_type, handler.clone());
+        }
+        Ok(())
+    }
+}
+
+/// AMM 풀 생성 이벤트
+pub struct CreatePoolHandler;
+
+#[async_trait]
+impl EventHandler for CreatePoolHandler {
+    async fn handle(&self, ctx: &HandlerContext<'_>, event: SuiEvent) -> Result<(), Error> {
+        let create_pool = decode::<MoveCreatePoolEvent>(&event)?;
+        create_pool_event(ctx, create_pool, event).await
+    }
+}
+
+/// AMM 스왑 이벤트
+pub struct SwapHandler;
+
+#[async_trait]
+impl EventHandler for SwapHandler {
+    async fn handle(&self, ctx: &HandlerContext<'_>, event: SuiEvent) -> Result<(), Error> {
+        let swap = decode::<MoveSwapEvent>(&event)?;
+        control_swap_event(ctx, swap, event).await
+    }
+}
+
+/// AMM 유동성 공급/회수 이벤트
+pub struct LiquidityHandler(pub LiquidityActionType);
+
+#[async_trait]
+impl EventHandler for LiquidityHandler {
+    async fn handle(&self, ctx: &HandlerContext<'_>, event: SuiEvent) -> Result<(), Error> {
+        let liquidity = match self.0 {
+            LiquidityActionType::Add => decode::<MoveAddLiquidityEvent>(&event)?.0,
+            LiquidityActionType::Remove => decode::<MoveRemoveLiquidityEvent>(&event)?.0,
+        };
+        liquidity_event(ctx, liquidity, self.0, event).await
+    }
+}
+
+/// 본딩 커브 졸업 이벤트
+pub struct GraduateHandler;
+
+#[async_trait]
+impl EventHandler for GraduateHandler {
+    async fn handle(&self, ctx: &HandlerContext<'_>, event: SuiEvent) -> Result<(), Error> {
+        let graduate = decode::<MoveGraduateEvent>(&event)?;
+        let lifecycle = Lifecycle::Graduated {
+            graduated_at: event.timestamp_ms.or_missing("timestamp_ms")?,
+        };
+        lifecycle_event(ctx, graduate.pool_id, lifecycle).await
+    }
+}
+
+/// 외부 DEX 이전 이벤트
+pub struct MigratePoolHandler;
+
+#[async_trait]
+impl EventHandler for MigratePoolHandler {
+    async fn handle(&self, ctx: &HandlerContext<'_>, event: SuiEvent) -> Result<(), Error> {
+        let migrate = decode::<MoveMigratePoolEvent>(&event)?;
+        let lifecycle = Lifecycle::Migrated {
+            venue: migrate.venue,
+            target_pool_id: migrate.target_pool_id.to_string(),
+            migrated_at: event.timestamp_ms.or_missing("timestamp_ms")?,
+        };
+        lifecycle_event(ctx, migrate.pool_id, lifecycle).await
+    }
+}
+
+/// 외부 DEX 스왑 이벤트
+///
+/// AMM에서 옮겨간 풀의 스왑만 처리하고 나머지는 조회 없이 버립니다.
+pub struct DexSwapHandler {
+    venue: Venue,
+    /// 풀의 코인 타입은 바뀌지 않으므로 한 번만 조회합니다.
+    pool_coins: Mutex<HashMap<ObjectID, (TypeTag, TypeTag)>>,
+}
+
+impl DexSwapHandler {
+    pub fn new(venue: Venue) -> Self {
+        DexSwapHandler {
+            venue,
+            pool_coins: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for DexSwapHandler {
+    async fn handle(&self, ctx: &HandlerContext<'_>, event: SuiEvent) -> Result<(), Error> {
+        let swap = DexSwap::parse(self.venue, &event)?;
+        let Some(coin_type) = ctx.migration_targets.get(&swap.pool_id).await else {
+            return Ok(());
+        };
+        let cached = self.pool_coins.lock().await.get(&swap.pool_id).cloned();
+        let coins = match cached {
+            Some(coins) => coins,
+            None => {
+                let coins = get_pool_coins(ctx.sui.clone(), swap.pool_id).await?;
+                self.pool_coins
+                    .lock()
+                    .await
+                    .insert(swap.pool_id, coins.clone());
+                coins
+            }
+        };
+        dex_swap_event(ctx, swap, coin_type, coins, event).await
+    }
+}
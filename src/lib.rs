@@ -1,7 +1,19 @@
-pub mod env;
+pub mod api;
+pub mod config;
+pub mod error;
+pub mod event;
+pub mod handler;
+pub mod metrics;
 pub mod observe;
+pub mod reconcile;
+pub mod retry;
+pub mod rpc_proxy;
+pub mod shutdown;
 
 pub mod db;
+pub mod dex;
 
 pub mod sui;
+pub mod supervisor;
+pub mod telemetry;
 pub mod utils;
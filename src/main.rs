@@ -1,44 +1,268 @@
 use anyhow::Result;
+use chrono::Utc;
 
 use gmi_server::{
-    db::Database,
-    env::{self, get_env},
-    observe::{receive_event, subscribe_package_event},
-    sui,
-};
-use log::info;
-use std::{str::FromStr, sync::Arc};
-use sui_sdk::{
-    rpc_types::{EventFilter, SuiEvent},
-    types::{base_types::ObjectID, Identifier},
+    api::{
+        self,
+        ops::{LagCheck, OpsState},
+    },
+    config::Config,
+    db::{model::MarketUpdate, Database},
+    handler::HandlerRegistry,
+    metrics,
+    observe::{
+        receive_event, reprocess_dead_letters, subscribe_package_event, AccountLocks,
+        CoinTypeCache, EventChannel, EventPipeline, EventSource, MigrationTargets,
+    },
+    reconcile::run_reconciler,
+    shutdown,
+    sui::SuiClientPool,
+    supervisor::Supervisor,
+    telemetry,
 };
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use sui_sdk::rpc_types::SuiEvent;
 use tokio::{
-    sync::broadcast::{self, Receiver, Sender},
+    sync::{
+        broadcast::{self, Receiver, Sender},
+        mpsc, Mutex,
+    },
     task::JoinSet,
 };
+use tracing::{error, info};
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(25);
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    tracing_subscriber::fmt()
-        .with_max_level(tracing::Level::INFO)
-        .init();
     dotenv::dotenv().ok();
-    let db = Arc::new(Database::new().await?);
-    // get_sui_price().await?;
-    let sui = Arc::new(sui::get_client(get_env("SUI_RPC").as_str()).await);
-    // info!("Sui client initialized");
-    let (event_sender, event_receiver): (Sender<SuiEvent>, Receiver<SuiEvent>) =
-        broadcast::channel(100);
+    metrics::init();
+    // observer | api | all | reprocess
+    let mode = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "observer".to_string());
+    let run_observer = matches!(mode.as_str(), "observer" | "all");
+    let run_api = matches!(mode.as_str(), "api" | "all");
+    let reprocess = mode == "reprocess";
+    if !run_observer && !run_api && !reprocess {
+        anyhow::bail!(
+            "Invalid mode: {} (expected observer, api, all or reprocess)",
+            mode
+        );
+    }
+
+    let config = Config::load()?;
+    telemetry::init(&config.log, config.otlp.as_ref())?;
+
+    let mut handlers = HandlerRegistry::amm(&config.package)?;
+    handlers.register_dex(&config.dex)?;
+    let handlers = Arc::new(handlers);
+    let db = Arc::new(Database::new(&config.db).await?);
+    let (update_sender, _): (Sender<MarketUpdate>, Receiver<MarketUpdate>) =
+        broadcast::channel(config.channel.update_capacity);
+
+    // dead letter로 저장된 이벤트를 한 번 다시 처리하고 종료합니다.
+    if reprocess {
+        let pipeline = EventPipeline {
+            pool: Arc::new(SuiClientPool::new(&config.network).await?),
+            coin_types: Arc::new(CoinTypeCache::load(&db).await?),
+            migration_targets: Arc::new(MigrationTargets::load(&db).await?),
+            db,
+            update_sender,
+            packages: Arc::new(config.package.clone()),
+            handlers,
+            account_locks: Arc::new(AccountLocks::default()),
+            retry: config.retry.clone(),
+        };
+        let result = reprocess_dead_letters(pipeline).await;
+        telemetry::shutdown();
+        return result;
+    }
+
+    let (shutdown_sender, shutdown) = shutdown::channel();
+    let supervisor = Supervisor::new(config.supervisor.clone());
     let mut set = JoinSet::new();
-    set.spawn(subscribe_package_event(sui.clone(), event_sender.clone()));
-    set.spawn(receive_event(sui.clone(), event_receiver, db.clone()));
+    let mut sui_pool = None;
+    let mut lag = None;
+
+    if run_observer {
+        // get_sui_price().await?;
+        let sui = Arc::new(SuiClientPool::new(&config.network).await?);
+        sui_pool = Some(sui.clone());
+        let coin_types = Arc::new(CoinTypeCache::load(&db).await?);
+        let migration_targets = Arc::new(MigrationTargets::load(&db).await?);
+        set.spawn(
+            supervisor
+                .clone()
+                .supervise("health_check", shutdown.clone(), {
+                    let (sui, shutdown) = (sui.clone(), shutdown.clone());
+                    move || sui.clone().run_health_checks(shutdown.clone())
+                }),
+        );
+        // info!("Sui client initialized");
+        // 채널마다 receive_event 하나만 소비하므로 backpressure가 있는 mpsc를 사용합니다.
+        // 외부 DEX 이벤트가 밀려도 AMM 구독이 멈추지 않도록 채널을 나눕니다.
+        let channels: HashMap<EventChannel, (mpsc::Sender<SuiEvent>, mpsc::Receiver<SuiEvent>)> =
+            EventChannel::ALL
+                .into_iter()
+                .map(|channel| (channel, mpsc::channel(config.channel.event_capacity)))
+                .collect();
+        let mut sources: Vec<EventSource> = config
+            .package
+            .amm_packages
+            .iter()
+            .map(|package| EventSource::amm(package.package_id, config.package.amm_module.clone()))
+            .collect();
+        // 처리 지연은 AMM 이벤트로만 잽니다.
+        lag = sources.first().map(|source| {
+            LagCheck::new(
+                source.clone(),
+                config.ops.max_lag,
+                Utc::now().timestamp_millis() as u64,
+            )
+        });
+        for venue in &config.dex.venues {
+            sources.push(EventSource::dex(venue.venue, venue.package_id)?);
+        }
+        for source in sources {
+            let name = format!("subscribe_package_event:{}", source.package_id);
+            set.spawn(supervisor.clone().supervise(name, shutdown.clone(), {
+                let (sui, db, event_sender, shutdown) = (
+                    sui.clone(),
+                    db.clone(),
+                    channels[&source.channel].0.clone(),
+                    shutdown.clone(),
+                );
+                move || {
+                    subscribe_package_event(
+                        sui.clone(),
+                        db.clone(),
+                        source.clone(),
+                        event_sender.clone(),
+                        shutdown.clone(),
+                    )
+                }
+            }));
+        }
+        let pipeline = EventPipeline {
+            pool: sui.clone(),
+            db: db.clone(),
+            update_sender: update_sender.clone(),
+            packages: Arc::new(config.package.clone()),
+            handlers: handlers.clone(),
+            coin_types: coin_types.clone(),
+            migration_targets: migration_targets.clone(),
+            account_locks: Arc::new(AccountLocks::default()),
+            retry: config.retry.clone(),
+        };
+        for (channel, (_, event_receiver)) in channels {
+            // 구독하는 DEX가 없으면 DEX 채널은 비어 있으므로 처리 태스크를 띄우지 않습니다.
+            if channel == EventChannel::Dex && config.dex.venues.is_empty() {
+                continue;
+            }
+            let name = format!("receive_event:{}", channel.name());
+            set.spawn(supervisor.clone().supervise(name, shutdown.clone(), {
+                let event_receiver = Arc::new(Mutex::new(event_receiver));
+                let pipeline = pipeline.clone();
+                move || receive_event(pipeline.clone(), channel, event_receiver.clone())
+            }));
+        }
+        set.spawn(
+            supervisor
+                .clone()
+                .supervise("reconcile_pools", shutdown.clone(), {
+                    let (sui, db, update_sender, shutdown) = (
+                        sui.clone(),
+                        db.clone(),
+                        update_sender.clone(),
+                        shutdown.clone(),
+                    );
+                    let interval = config.reconcile.interval;
+                    move || {
+                        run_reconciler(
+                            sui.clone(),
+                            db.clone(),
+                            update_sender.clone(),
+                            interval,
+                            shutdown.clone(),
+                        )
+                    }
+                }),
+        );
+    }
 
+    if run_api {
+        set.spawn(supervisor.clone().supervise("api", shutdown.clone(), {
+            // `api` 모드에서는 업데이트를 보내는 observer가 없으므로 실시간 스트림을 열지 않습니다.
+            let (db, update_sender, components, shutdown) = (
+                db.clone(),
+                run_observer.then(|| update_sender.clone()),
+                supervisor.states(),
+                shutdown.clone(),
+            );
+            let api_config = config.api.clone();
+            move || {
+                api::serve(
+                    api_config.clone(),
+                    db.clone(),
+                    update_sender.clone(),
+                    components.clone(),
+                    shutdown.clone(),
+                )
+            }
+        }));
+    }
+
+    set.spawn(supervisor.clone().supervise("ops", shutdown.clone(), {
+        let state = OpsState {
+            db: db.clone(),
+            sui: sui_pool,
+            components: supervisor.states(),
+            event_capacity: config.channel.event_capacity,
+            lag,
+        };
+        let (addr, shutdown) = (config.ops.addr, shutdown.clone());
+        move || api::ops::serve(addr, state.clone(), shutdown.clone())
+    }));
+
+    let escalated = tokio::select! {
+        _ = shutdown::wait_for_signal() => {
+            info!("Shutting down, draining pending events");
+            None
+        }
+        failure = join_tasks(&mut set) => failure,
+    };
+    if let Some(e) = &escalated {
+        error!(error = ?e, "Shutting down after unrecoverable failure");
+    }
+
+    // 구독을 멈추면 이벤트 채널이 닫히고, receive_event가 남은 이벤트를 처리한 뒤 cursor를 저장합니다.
+    shutdown_sender.send_replace(true);
+    let drain = async {
+        while let Some(e) = join_tasks(&mut set).await {
+            error!(error = ?e, "Task failed during shutdown");
+        }
+    };
+    if tokio::time::timeout(SHUTDOWN_TIMEOUT, drain).await.is_err() {
+        error!("Shutdown timed out, aborting remaining tasks");
+        set.abort_all();
+    }
+
+    telemetry::shutdown();
+    match escalated {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// 태스크가 실패하면(재시작 한도 초과) 그 오류를 반환합니다.
+async fn join_tasks(set: &mut JoinSet<Result<()>>) -> Option<anyhow::Error> {
     while let Some(res) = set.join_next().await {
         match res {
-            Ok(_) => println!("Task completed successfully"),
-            Err(e) => eprintln!("Task panicked: {:?}", e),
+            Ok(Ok(_)) => info!("Task completed"),
+            Ok(Err(e)) => return Some(e),
+            Err(e) => return Some(e.into()),
         }
     }
-
-    Ok(())
+    None
 }
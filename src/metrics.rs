@@ -0,0 +1,257 @@
+use std::future::Future;
+
+use once_cell::sync::Lazy;
+use prometheus::{
+    register_histogram_vec, register_int_counter, register_int_counter_vec, register_int_gauge,
+    register_int_gauge_vec, Encoder, HistogramTimer, HistogramVec, IntCounter, IntCounterVec,
+    IntGauge, IntGaugeVec, TextEncoder,
+};
+use tracing::{info_span, Instrument};
+
+// 구독
+
+/// 패키지·이벤트별로 받은 이벤트 수
+pub static EVENTS_RECEIVED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "gmi_events_received_total",
+        "Events received from the subscription",
+        &["package", "event"]
+    )
+    .unwrap()
+});
+/// 노드가 스트림을 닫아 다시 구독했을 때 놓친 이벤트가 있었던 횟수
+pub static LAGGED_SUBSCRIPTIONS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "gmi_lagged_subscriptions_total",
+        "Event streams closed by the node that missed events before resubscribing"
+    )
+    .unwrap()
+});
+/// cursor로 다시 가져온 이벤트 수
+pub static REPLAYED_EVENTS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "gmi_replayed_events_total",
+        "Events fetched by cursor after a resubscription"
+    )
+    .unwrap()
+});
+/// AMM 패키지가 발생시켰지만 등록된 처리기가 없는 이벤트 수
+pub static UNHANDLED_AMM_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "gmi_unhandled_amm_events_total",
+        "Events from AMM packages without a registered handler",
+        &["package", "event"]
+    )
+    .unwrap()
+});
+/// 채널(amm, dex)별로 구독과 처리 사이에 쌓인 이벤트 수
+pub static EVENT_CHANNEL_DEPTH: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "gmi_event_channel_depth",
+        "Events waiting in the channel between subscribers and receive_event",
+        &["channel"]
+    )
+    .unwrap()
+});
+
+// 처리
+
+/// 이벤트·결과(ok 또는 오류 종류)별로 처리한 이벤트 수
+pub static EVENTS_PROCESSED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "gmi_events_processed_total",
+        "Events handled, by event and result (ok, parse, missing, rpc or db)",
+        &["event", "result"]
+    )
+    .unwrap()
+});
+pub static HANDLER_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "gmi_handler_duration_seconds",
+        "Event handler latency",
+        &["event"]
+    )
+    .unwrap()
+});
+/// 처리에 실패해 다시 시도한 횟수
+pub static RETRIED_EVENTS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!("gmi_retried_events_total", "Event handling retries").unwrap()
+});
+/// BCS로 디코딩한 값이 `parsed_json`과 달라 `parsed_json`을 쓴 이벤트 수
+pub static EVENT_LAYOUT_MISMATCHES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "gmi_event_layout_mismatches_total",
+        "Events whose BCS decoding differed from parsed_json, by Move struct",
+        &["event"]
+    )
+    .unwrap()
+});
+/// 재시도 횟수를 넘겨 dead letter로 옮긴 이벤트 수
+pub static DEAD_LETTERED_EVENTS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "gmi_dead_lettered_events_total",
+        "Events moved to dead letters after exhausting retries"
+    )
+    .unwrap()
+});
+/// 마지막으로 처리한 AMM 이벤트의 체인 시각 (ms)
+pub static LAST_EVENT_TIMESTAMP: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "gmi_last_event_timestamp_ms",
+        "Chain timestamp of the last handled AMM event"
+    )
+    .unwrap()
+});
+/// 최신 체크포인트 시각 (ms)
+pub static CHAIN_HEAD_TIMESTAMP: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "gmi_chain_head_timestamp_ms",
+        "Timestamp of the latest checkpoint seen by the health check"
+    )
+    .unwrap()
+});
+/// 최신 체크포인트와 마지막으로 처리한 이벤트의 시각 차이 (ms)
+pub static CHAIN_LAG: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "gmi_chain_lag_ms",
+        "Latest checkpoint timestamp minus the last handled event timestamp"
+    )
+    .unwrap()
+});
+
+// 풀 보정
+
+/// 저장된 reserve가 체인과 달라 보정한 풀 수
+pub static POOL_DRIFTS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "gmi_pool_drifts_total",
+        "Pools whose stored reserves differed from the on-chain object and were corrected"
+    )
+    .unwrap()
+});
+/// 인덱싱하지 않은 전송 등으로 음수가 된 계정 잔고 수
+pub static NEGATIVE_BALANCES: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "gmi_negative_balances_total",
+        "Balance changes that left an account balance below zero"
+    )
+    .unwrap()
+});
+/// 풀 객체를 읽거나 보정하지 못한 횟수
+pub static POOL_RECONCILE_ERRORS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "gmi_pool_reconcile_errors_total",
+        "Pools that could not be reconciled"
+    )
+    .unwrap()
+});
+
+// RPC / DB
+
+pub static RPC_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!("gmi_rpc_duration_seconds", "Sui RPC latency", &["method"]).unwrap()
+});
+pub static RPC_ERRORS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!("gmi_rpc_errors_total", "Failed Sui RPC calls", &["method"]).unwrap()
+});
+pub static RPC_FAILOVERS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "gmi_rpc_failovers_total",
+        "Switches to another Sui RPC node"
+    )
+    .unwrap()
+});
+pub static DB_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "gmi_db_duration_seconds",
+        "Database method latency",
+        &["method"]
+    )
+    .unwrap()
+});
+
+// API
+
+/// 느린 WebSocket 클라이언트가 놓친 업데이트 수
+pub static LAGGED_UPDATES: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "gmi_ws_lagged_updates_total",
+        "Market updates skipped by slow WebSocket clients"
+    )
+    .unwrap()
+});
+
+/// RPC 호출 시간을 기록하고 실패를 집계합니다. 호출마다 `rpc` span을 엽니다.
+pub async fn rpc<T, E>(method: &str, request: impl Future<Output = Result<T, E>>) -> Result<T, E> {
+    let timer = RPC_DURATION.with_label_values(&[method]).start_timer();
+    let result = request.instrument(info_span!("rpc", method)).await;
+    timer.observe_duration();
+    if result.is_err() {
+        RPC_ERRORS.with_label_values(&[method]).inc();
+    }
+    result
+}
+
+/// drop될 때 DB 메서드 실행 시간을 기록합니다.
+pub fn db_timer(method: &str) -> HistogramTimer {
+    DB_DURATION.with_label_values(&[method]).start_timer()
+}
+
+/// 이벤트를 처리한 뒤 마지막 이벤트 시각과 체인 지연을 갱신합니다.
+pub fn record_event_timestamp(timestamp_ms: u64) {
+    LAST_EVENT_TIMESTAMP.set(LAST_EVENT_TIMESTAMP.get().max(timestamp_ms as i64));
+    update_chain_lag();
+}
+
+pub fn record_chain_head(timestamp_ms: u64) {
+    CHAIN_HEAD_TIMESTAMP.set(CHAIN_HEAD_TIMESTAMP.get().max(timestamp_ms as i64));
+    update_chain_lag();
+}
+
+/// 최신 체크포인트와 이벤트 시각을 모두 관측한 뒤의 체인 지연 (ms)
+pub fn chain_lag_ms() -> Option<i64> {
+    let (head, last) = (CHAIN_HEAD_TIMESTAMP.get(), LAST_EVENT_TIMESTAMP.get());
+    (head > 0 && last > 0).then(|| (head - last).max(0))
+}
+
+fn update_chain_lag() {
+    if let Some(lag) = chain_lag_ms() {
+        CHAIN_LAG.set(lag);
+    }
+}
+
+/// 등록된 모든 메트릭을 Prometheus 텍스트 형식으로 인코딩합니다.
+pub fn encode() -> Result<String, prometheus::Error> {
+    let mut buffer = Vec::new();
+    TextEncoder::new().encode(&prometheus::gather(), &mut buffer)?;
+    Ok(String::from_utf8_lossy(&buffer).into_owned())
+}
+
+/// 아직 기록되지 않은 메트릭도 처음부터 노출되도록 미리 등록합니다.
+pub fn init() {
+    for counter in [
+        &LAGGED_SUBSCRIPTIONS,
+        &REPLAYED_EVENTS,
+        &RETRIED_EVENTS,
+        &DEAD_LETTERED_EVENTS,
+        &RPC_FAILOVERS,
+        &POOL_DRIFTS,
+        &POOL_RECONCILE_ERRORS,
+        &NEGATIVE_BALANCES,
+        &LAGGED_UPDATES,
+    ] {
+        Lazy::force(counter);
+    }
+    for gauge in [&LAST_EVENT_TIMESTAMP, &CHAIN_HEAD_TIMESTAMP, &CHAIN_LAG] {
+        Lazy::force(gauge);
+    }
+    Lazy::force(&EVENT_CHANNEL_DEPTH);
+    Lazy::force(&EVENTS_RECEIVED);
+    Lazy::force(&UNHANDLED_AMM_EVENTS);
+    Lazy::force(&EVENTS_PROCESSED);
+    Lazy::force(&EVENT_LAYOUT_MISMATCHES);
+    Lazy::force(&HANDLER_DURATION);
+    Lazy::force(&RPC_DURATION);
+    Lazy::force(&RPC_ERRORS);
+    Lazy::force(&DB_DURATION);
+}
@@ -1,184 +1,1261 @@
 use crate::{
+    config::{AmmPackage, PackageConfig, RetryConfig},
     db::{
-        model::{CreatePoolEvent, SwapEvent},
+        model::{
+            AccountBalance, BalanceChange, Chart, CreatePoolEvent, DeadLetter, EventCursor,
+            Lifecycle, LiquidityAction, LiquidityActionType, MarketUpdate, SwapEvent, Trade,
+        },
         Database,
     },
-    env,
+    dex::{DexSwap, Venue},
+    error::{Error, OrMissing},
+    event::{MoveCreatePoolEvent, MoveLiquidityEvent, MoveSwapEvent},
+    handler::{HandlerContext, HandlerRegistry},
+    metrics,
+    retry::{PendingRetry, RetryQueue},
+    shutdown::{self, Shutdown},
+    sui::SuiClientPool,
 };
 
 // use crate::bot::amm::AMM;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
 use regex::Regex;
 use rust_decimal::Decimal;
 
-use std::{str::FromStr, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+use sui_move_types::language_storage::TypeTag;
 use sui_sdk::{
-    rpc_types::{EventFilter, SuiEvent, SuiObjectDataOptions},
-    types::base_types::{ObjectID, SuiAddress},
+    rpc_types::{
+        EventFilter, SuiEvent, SuiObjectDataOptions, SuiTransactionBlockEffectsAPI,
+        SuiTransactionBlockResponseOptions,
+    },
+    types::{
+        base_types::{ObjectID, SuiAddress},
+        event::EventID,
+        gas_coin::GAS,
+        object::Owner,
+        parse_sui_struct_tag, parse_sui_type_tag, Identifier,
+    },
     SuiClient,
 };
-use tokio::sync::broadcast::{Receiver, Sender};
+use tokio::{
+    sync::{broadcast::Sender, mpsc, Mutex, OwnedMutexGuard, RwLock},
+    time::MissedTickBehavior,
+};
 use tokio_stream::StreamExt;
-use tracing::info;
+use tracing::{debug, error, field, info, info_span, instrument, warn, Instrument, Span};
+
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(1);
+/// 처리한 이벤트가 이만큼 쌓이거나 `CURSOR_SAVE_INTERVAL`이 지나면 cursor를 저장합니다.
+const CURSOR_SAVE_EVERY: usize = 100;
+const CURSOR_SAVE_INTERVAL: Duration = Duration::from_secs(5);
+static POOL_TYPE: Lazy<Regex> = Lazy::new(|| Regex::new(r"::Pool<([^>]+)>").unwrap());
+
+/// 구독한 이벤트를 처리로 넘기는 채널
+///
+/// 외부 DEX 이벤트가 밀려도 AMM 이벤트 처리를 막지 않도록 채널과 처리 태스크를 나눕니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventChannel {
+    Amm,
+    Dex,
+}
+
+impl EventChannel {
+    pub const ALL: [EventChannel; 2] = [EventChannel::Amm, EventChannel::Dex];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventChannel::Amm => "amm",
+            EventChannel::Dex => "dex",
+        }
+    }
+}
+
+/// 구독 하나의 설정
+#[derive(Debug, Clone)]
+pub struct EventSource {
+    /// cursor를 저장하는 키. 패키지마다 구독은 하나여야 합니다.
+    pub package_id: ObjectID,
+    pub channel: EventChannel,
+    pub filter: EventFilter,
+    /// 놓친 구간을 조회하는 방법
+    pub replay: Replay,
+}
+
+/// 노드의 이벤트 조회(`suix_queryEvents`)는 구독과 달리 `Package` 필터를 지원하지 않으므로
+/// AMM 패키지는 다른 필터로 조회합니다.
+#[derive(Debug, Clone)]
+pub enum Replay {
+    /// 구독 필터로 그대로 조회합니다.
+    Filter,
+    /// 이벤트 타입을 정의한 모듈(`MoveEventModule`)로 조회하고 이 패키지가 발생시킨 이벤트만 남깁니다.
+    /// 업그레이드된 패키지의 이벤트 타입은 원래 패키지 주소를 쓰므로 처음 조회할 때 원래 주소를 찾습니다.
+    EventModule(Identifier),
+}
+
+impl EventSource {
+    /// AMM 패키지가 발생시킨 모든 이벤트를 구독합니다. `module`은 이벤트를 정의한 모듈입니다.
+    pub fn amm(package_id: ObjectID, module: Identifier) -> Self {
+        EventSource {
+            package_id,
+            channel: EventChannel::Amm,
+            filter: EventFilter::Package(package_id),
+            replay: Replay::EventModule(module),
+        }
+    }
+
+    /// 외부 DEX의 스왑 이벤트를 구독합니다.
+    pub fn dex(venue: Venue, package_id: ObjectID) -> Result<Self> {
+        Ok(EventSource {
+            package_id,
+            channel: EventChannel::Dex,
+            filter: venue.filter(package_id)?,
+            replay: Replay::Filter,
+        })
+    }
+}
+
+/// 패키지 이벤트를 구독합니다. 저장된 cursor 이후의 이벤트부터 이어서 받습니다.
 pub async fn subscribe_package_event(
-    sui: Arc<SuiClient>,
-    event_sender: Sender<SuiEvent>,
+    pool: Arc<SuiClientPool>,
+    db: Arc<Database>,
+    source: EventSource,
+    event_sender: mpsc::Sender<SuiEvent>,
+    shutdown: Shutdown,
 ) -> Result<()> {
-    info!("Subscribing to package events start");
-    let amm_package_id = ObjectID::from_str(&env::get_env("AMM_PACKAGE_ID"))?;
-    let mut event_stream = sui
-        .event_api()
-        .subscribe_event(EventFilter::Package(amm_package_id))
-        .await?;
+    let cursor: Option<EventID> = db
+        .get_event_cursor(&source.package_id.to_string())
+        .await?
+        .map(|cursor| cursor.event_id())
+        .transpose()?;
+    follow_events(pool, source, cursor, event_sender, shutdown).await
+}
 
-    while let Some(event_result) = event_stream.next().await {
-        match event_result {
-            Ok(event) => {
-                info!("Event Send");
-                if let Err(e) = event_sender.send(event) {
-                    eprintln!("Error sending event: {:?}", e);
+/// `cursor` 이후의 이벤트를 계속 받아 채널로 보냅니다.
+///
+/// 구독 버퍼가 넘치면 노드가 스트림을 닫으므로 같은 노드에 다시 구독하고 놓친 구간을 cursor로 조회합니다.
+/// 연결 오류가 나면 다른 노드로 전환합니다. 종료 요청을 받으면 구독을 멈춥니다.
+pub async fn follow_events(
+    pool: Arc<SuiClientPool>,
+    source: EventSource,
+    mut cursor: Option<EventID>,
+    event_sender: mpsc::Sender<SuiEvent>,
+    mut shutdown: Shutdown,
+) -> Result<()> {
+    info!(package_id = %source.package_id, "Subscribing to package events");
+    let mut replay_filter = None;
+    // 직전 스트림이 노드 쪽에서 닫혔는지. 다시 구독한 뒤 놓친 이벤트가 있을 때만 지연으로 셉니다.
+    let mut closed = false;
+
+    loop {
+        let sui = pool.client();
+        info!(endpoint = %pool.current_endpoint().ws_url, "Subscribing");
+        let stream = stream_package_event(
+            &sui,
+            &source,
+            &mut replay_filter,
+            &mut cursor,
+            &event_sender,
+            closed,
+        );
+        tokio::select! {
+            result = stream => match result {
+                Ok(()) => {
+                    closed = true;
+                    info!("Event stream closed, resubscribing from cursor");
                 }
+                Err(e) => {
+                    closed = false;
+                    error!(error = ?e, "Event subscription failed");
+                    pool.failover();
+                }
+            },
+            _ = shutdown::requested(&mut shutdown) => break,
+        }
+        if event_sender.is_closed() {
+            warn!("Event receiver closed");
+            break;
+        }
+        tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+    }
+
+    info!(package_id = %source.package_id, "Stopped subscribing to package events");
+    Ok(())
+}
+
+async fn stream_package_event(
+    sui: &SuiClient,
+    source: &EventSource,
+    replay_filter: &mut Option<EventFilter>,
+    cursor: &mut Option<EventID>,
+    event_sender: &mpsc::Sender<SuiEvent>,
+    after_close: bool,
+) -> Result<()> {
+    // 구독을 먼저 연 뒤 cursor 이후 이벤트를 조회해야 전환 중에 놓치는 이벤트가 없습니다.
+    let mut event_stream = metrics::rpc(
+        "subscribe_event",
+        sui.event_api().subscribe_event(source.filter.clone()),
+    )
+    .await?;
+
+    let mut replayed = HashSet::new();
+    let mut next_cursor = cursor.clone();
+    if next_cursor.is_some() && replay_filter.is_none() {
+        *replay_filter = Some(resolve_replay_filter(sui, source).await?);
+    }
+    while let Some(from) = next_cursor.take() {
+        let filter = replay_filter.clone().or_missing("replay filter")?;
+        let page = metrics::rpc(
+            "query_events",
+            sui.event_api()
+                .query_events(filter, Some(from), None, false),
+        )
+        .await?;
+        for event in page.data {
+            *cursor = Some(event.id.clone());
+            // 모듈 단위 조회에는 같은 타입을 쓰는 다른 버전의 이벤트도 섞여 옵니다.
+            if matches!(source.replay, Replay::EventModule(_))
+                && event.package_id != source.package_id
+            {
+                continue;
             }
-            Err(e) => {
-                eprintln!("Error receiving event: {:?}", e);
-            }
+            replayed.insert(event.id.clone());
+            send_event(source.channel, event_sender, event).await?;
+        }
+        if page.has_next_page {
+            next_cursor = page.next_cursor;
         }
     }
-    info!("Subscribing to package events end");
+    if !replayed.is_empty() {
+        metrics::REPLAYED_EVENTS.inc_by(replayed.len() as u64);
+        info!(count = replayed.len(), "Replayed missed events");
+        if after_close {
+            metrics::LAGGED_SUBSCRIPTIONS.inc();
+            warn!(
+                missed = replayed.len(),
+                lagged = metrics::LAGGED_SUBSCRIPTIONS.get(),
+                "Event stream closed with events missed"
+            );
+        }
+    }
+
+    while let Some(event_result) = event_stream.next().await {
+        let event = event_result.map_err(|e| {
+            metrics::RPC_ERRORS
+                .with_label_values(&["subscribe_event"])
+                .inc();
+            e
+        })?;
+        if replayed.contains(&event.id) {
+            continue;
+        }
+        *cursor = Some(event.id.clone());
+        send_event(source.channel, event_sender, event).await?;
+    }
     Ok(())
 }
 
+/// 놓친 구간을 조회할 필터를 정합니다.
+///
+/// 업그레이드된 패키지의 모듈은 원래 패키지 주소를 자기 주소로 유지하므로 정규화된 모듈의 주소가
+/// 이벤트 타입의 주소입니다.
+pub async fn resolve_replay_filter(sui: &SuiClient, source: &EventSource) -> Result<EventFilter> {
+    let module = match &source.replay {
+        Replay::Filter => return Ok(source.filter.clone()),
+        Replay::EventModule(module) => module,
+    };
+    let modules = metrics::rpc(
+        "get_normalized_move_modules_by_package",
+        sui.read_api()
+            .get_normalized_move_modules_by_package(source.package_id),
+    )
+    .await?;
+    let address = &modules
+        .get(module.as_str())
+        .ok_or_else(|| anyhow!("Module {} not found in {}", module, source.package_id))?
+        .address;
+    let package = ObjectID::from_hex_literal(address)?;
+    info!(package_id = %source.package_id, original_id = %package, "Resolved event type package");
+    Ok(EventFilter::MoveEventModule {
+        package,
+        module: module.clone(),
+    })
+}
+
+/// 채널이 가득 차면 receive_event가 따라올 때까지 기다립니다.
+async fn send_event(
+    channel: EventChannel,
+    event_sender: &mpsc::Sender<SuiEvent>,
+    event: SuiEvent,
+) -> Result<()> {
+    debug!(tx_digest = %event.id.tx_digest, event_seq = event.id.event_seq, "Event sent");
+    event_sender
+        .send(event)
+        .await
+        .map_err(|_| anyhow!("Event channel closed"))?;
+    metrics::EVENT_CHANNEL_DEPTH
+        .with_label_values(&[channel.name()])
+        .set((event_sender.max_capacity() - event_sender.capacity()) as i64);
+    Ok(())
+}
+
+/// 이벤트 처리에 필요한 공유 자원
+///
+/// 태스크가 재시작될 때마다 복제해 넘깁니다.
+#[derive(Clone)]
+pub struct EventPipeline {
+    pub pool: Arc<SuiClientPool>,
+    pub db: Arc<Database>,
+    pub update_sender: Sender<MarketUpdate>,
+    pub packages: Arc<PackageConfig>,
+    pub handlers: Arc<HandlerRegistry>,
+    pub coin_types: Arc<CoinTypeCache>,
+    pub migration_targets: Arc<MigrationTargets>,
+    pub account_locks: Arc<AccountLocks>,
+    pub retry: RetryConfig,
+}
+
+impl EventPipeline {
+    fn context(&self, package_version: u64) -> HandlerContext<'_> {
+        HandlerContext {
+            sui: self.pool.client(),
+            db: self.db.clone(),
+            coin_types: &self.coin_types,
+            migration_targets: &self.migration_targets,
+            account_locks: &self.account_locks,
+            update_sender: &self.update_sender,
+            package_version,
+        }
+    }
+}
+
+/// 한 채널로 구독한 이벤트를 처리합니다.
+///
+/// 처리에 실패한 이벤트는 backoff 후 다시 시도하고, 시도 횟수를 모두 쓰면 dead letter로 저장합니다.
+/// cursor는 주기적으로 저장하므로 비정상 종료 후에는 마지막 저장 이후의 이벤트를 다시 받습니다.
 pub async fn receive_event(
-    sui: Arc<SuiClient>,
-    mut event_receiver: Receiver<SuiEvent>,
-    db: Arc<Database>,
+    pipeline: EventPipeline,
+    channel: EventChannel,
+    event_receiver: Arc<Mutex<mpsc::Receiver<SuiEvent>>>,
 ) -> Result<()> {
-    info!("Receive Event Start");
+    info!(channel = channel.name(), "Receiving events");
+    let EventPipeline {
+        db,
+        packages,
+        handlers,
+        ..
+    } = pipeline.clone();
+    // 재시작되어도 같은 채널을 이어서 받을 수 있도록 수신자를 공유합니다.
+    let mut event_receiver = event_receiver.lock().await;
+    // 패키지별로 마지막으로 처리했지만 아직 저장하지 않은 이벤트
+    let mut cursors: HashMap<ObjectID, EventID> = HashMap::new();
+    let mut handled_since_save = 0;
+    let mut save_interval = tokio::time::interval(CURSOR_SAVE_INTERVAL);
+    save_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut retries = RetryQueue::new(pipeline.retry.clone());
+    // DB에 남기지 못한 재시도 이벤트와 그 패키지. 남길 때까지 그 패키지의 cursor를 옮기지 않습니다.
+    let mut unsaved_retries: HashMap<String, ObjectID> = HashMap::new();
+    for record in db.get_pending_retries().await? {
+        match record.event() {
+            // 재시도는 한 테이블에 저장되므로 이 채널로 들어온 이벤트만 가져옵니다.
+            Ok(event) if event_channel(&packages, &event) != channel => {}
+            Ok(event) => retries.restore(
+                event,
+                record.package_version,
+                record.attempts,
+                record.last_error,
+            ),
+            Err(e) => error!(id = %record.id(), error = ?e, "Failed to restore pending retry"),
+        }
+    }
+    if !retries.is_empty() {
+        info!(count = retries.len(), "Restored pending retries");
+    }
 
-    while let Ok(event) = event_receiver.recv().await {
-        println!("Receive Event!!\n");
-        let event_name = event.type_.name.to_string();
+    loop {
+        let (event, package_version, attempts, cursor_key) = tokio::select! {
+            event = event_receiver.recv() => {
+                let Some(event) = event else { break };
+                metrics::EVENT_CHANNEL_DEPTH
+                    .with_label_values(&[channel.name()])
+                    .set(event_receiver.len() as i64);
+                let event_name = event.type_.name.to_string();
+                metrics::EVENTS_RECEIVED
+                    .with_label_values(&[&event.package_id.to_string(), &event_name])
+                    .inc();
 
-        match event_name.as_str() {
-            "CreatePoolEvent" => {
-                if let Err(e) = create_pool_event(sui.clone(), db.clone(), event).await {
-                    eprintln!("Error handling CreatePoolEvent: {:?}", e);
+                let type_package = ObjectID::from(event.type_.address);
+                let amm_package = amm_package(&packages, &event);
+                if handlers.resolve(&event).is_none() {
+                    match amm_package {
+                        // AMM 구독에서 처리기가 없는 이벤트는 설정이 빠졌을 수 있으므로 드러나게 남깁니다.
+                        Some(package) => {
+                            metrics::UNHANDLED_AMM_EVENTS
+                                .with_label_values(&[&package.package_id.to_string(), &event_name])
+                                .inc();
+                            warn!(
+                                event_type = %event.type_,
+                                package_id = %event.package_id,
+                                "Unhandled event from AMM package"
+                            );
+                        }
+                        // DEX 모듈 구독에는 처리하지 않는 이벤트도 섞여 오므로 debug로만 남깁니다.
+                        None => debug!(
+                            event_type = %event.type_,
+                            package_id = %event.package_id,
+                            "Unhandled event"
+                        ),
+                    }
+                    continue;
                 }
+                let (cursor_key, package_version) = match amm_package {
+                    Some(package) => (package.package_id, package.version),
+                    // 외부 DEX는 이벤트 타입을 정의한 패키지로 구독합니다.
+                    None => (type_package, 0),
+                };
+                (event, package_version, 0, Some(cursor_key))
+            }
+            retry = retries.next_due() => {
+                metrics::RETRIED_EVENTS.inc();
+                info!(
+                    tx_digest = %retry.event.id.tx_digest,
+                    event_seq = retry.event.id.event_seq,
+                    attempt = retry.attempts + 1,
+                    "Retrying event"
+                );
+                (retry.event, retry.package_version, retry.attempts, None)
             }
-            "SwapEvent" => {
-                if let Err(e) = control_swap_event(sui.clone(), db.clone(), event).await {
-                    eprintln!("Error handling SwapEvent: {:?}", e);
+            _ = save_interval.tick() => {
+                save_cursors(&db, &mut cursors).await;
+                handled_since_save = 0;
+                continue;
+            }
+        };
+        let event_id = event.id.clone();
+
+        let ctx = pipeline.context(package_version);
+        let timestamp_ms = event.timestamp_ms;
+        match handle_event(&handlers, ctx, event.clone()).await {
+            Ok(()) => {
+                // 지연 지표는 AMM 이벤트로만 잽니다. 외부 DEX 이벤트는 대부분 버려지고 시각도 AMM과 무관합니다.
+                if let (EventChannel::Amm, Some(timestamp_ms)) = (channel, timestamp_ms) {
+                    metrics::record_event_timestamp(timestamp_ms);
+                }
+                if attempts > 0 {
+                    delete_pending_retry(&db, &event_id).await;
                 }
+                unsaved_retries.remove(&DeadLetter::id_of(&event_id));
+            }
+            Err(e) => {
+                warn!(
+                    tx_digest = %event.id.tx_digest,
+                    event_seq = event.id.event_seq,
+                    event_type = %event.type_.name,
+                    attempt = attempts + 1,
+                    kind = e.kind(),
+                    error = %e,
+                    "Failed to handle event"
+                );
+                // 해석할 수 없거나 값이 빠진 이벤트는 다시 시도해도 같으므로 바로 dead letter로 보냅니다.
+                let failed = if e.is_transient() {
+                    // 재시작되어도 이어서 시도하도록 예약한 재시도는 DB에도 남깁니다.
+                    let record =
+                        DeadLetter::new(&event, package_version, attempts + 1, e.to_string());
+                    let failed =
+                        retries.schedule(event, package_version, attempts + 1, e.to_string());
+                    if failed.is_none() {
+                        let id = DeadLetter::id_of(&event_id);
+                        if save_pending_retry(&db, record).await {
+                            unsaved_retries.remove(&id);
+                        } else if let Some(cursor_key) = cursor_key {
+                            unsaved_retries.insert(id, cursor_key);
+                        }
+                    }
+                    failed
+                } else {
+                    Some(PendingRetry::failed(
+                        event,
+                        package_version,
+                        attempts + 1,
+                        e.to_string(),
+                    ))
+                };
+                if let Some(failed) = failed {
+                    save_dead_letter(
+                        &db,
+                        failed.event,
+                        failed.package_version,
+                        failed.attempts,
+                        failed.last_error,
+                    )
+                    .await;
+                    if attempts > 0 {
+                        delete_pending_retry(&db, &event_id).await;
+                    }
+                    unsaved_retries.remove(&DeadLetter::id_of(&event_id));
+                }
+            }
+        }
+
+        // 처리를 마친 (DB에 남긴 재시도 대기 포함) 이벤트까지만 cursor를 옮겨, 중간에 종료되어도 그 이후부터 다시 받습니다.
+        // 재시도를 DB에 남기지 못한 패키지는 다시 받을 수 있도록 cursor를 그대로 둡니다.
+        let held = |key: &ObjectID| unsaved_retries.values().any(|held| held == key);
+        if let Some(cursor_key) = cursor_key.filter(|key| !held(key)) {
+            cursors.insert(cursor_key, event_id);
+            handled_since_save += 1;
+            if handled_since_save >= CURSOR_SAVE_EVERY {
+                save_cursors(&db, &mut cursors).await;
+                handled_since_save = 0;
+            }
+        }
+    }
+
+    // 구독이 모두 끝나 채널이 닫히면 남은 이벤트까지 처리한 상태이므로 cursor를 저장합니다.
+    info!("Event channel closed");
+    // 재시도 대기 이벤트는 DB에 남아 있으므로 다음에 시작할 때 이어서 시도합니다.
+    if !retries.is_empty() {
+        info!(
+            count = retries.len(),
+            "Leaving pending retries for the next start"
+        );
+    }
+    for (package_id, event_id) in cursors.drain() {
+        db.save_event_cursor(EventCursor::new(&package_id, &event_id))
+            .await?;
+        info!(package_id = %package_id, "Saved event cursor");
+    }
+    Ok(())
+}
+
+/// 재시도 대기 이벤트를 DB에 남기고, 남겼는지 반환합니다.
+async fn save_pending_retry(db: &Database, record: serde_json::Result<DeadLetter>) -> bool {
+    let record = match record {
+        Ok(record) => record,
+        Err(e) => {
+            error!(error = ?e, "Failed to serialize event");
+            return false;
+        }
+    };
+    let id = record.id();
+    match db.save_pending_retry(record).await {
+        Ok(()) => true,
+        Err(e) => {
+            // 저장하지 못해도 메모리의 대기열로는 다시 시도합니다.
+            warn!(id = %id, error = ?e, "Failed to save pending retry");
+            false
+        }
+    }
+}
+
+async fn delete_pending_retry(db: &Database, event_id: &EventID) {
+    let id = DeadLetter::id_of(event_id);
+    if let Err(e) = db.delete_pending_retry(&id).await {
+        warn!(id = %id, error = ?e, "Failed to delete pending retry");
+    }
+}
+
+/// 이벤트를 발생시킨 설정된 AMM 패키지
+///
+/// 업그레이드된 패키지의 이벤트 타입은 원래 패키지 주소를 유지하므로 발생시킨 패키지로 먼저 찾습니다.
+fn amm_package<'a>(packages: &'a PackageConfig, event: &SuiEvent) -> Option<&'a AmmPackage> {
+    packages
+        .find(&event.package_id)
+        .or_else(|| packages.find(&ObjectID::from(event.type_.address)))
+}
+
+fn event_channel(packages: &PackageConfig, event: &SuiEvent) -> EventChannel {
+    match amm_package(packages, event) {
+        Some(_) => EventChannel::Amm,
+        None => EventChannel::Dex,
+    }
+}
+
+/// 처리한 이벤트의 cursor를 저장합니다. 저장하지 못한 cursor는 남겨 두고 다음에 다시 시도합니다.
+async fn save_cursors(db: &Database, cursors: &mut HashMap<ObjectID, EventID>) {
+    let pending: Vec<(ObjectID, EventID)> = cursors
+        .iter()
+        .map(|(package_id, event_id)| (*package_id, event_id.clone()))
+        .collect();
+    for (package_id, event_id) in pending {
+        match db
+            .save_event_cursor(EventCursor::new(&package_id, &event_id))
+            .await
+        {
+            Ok(()) => {
+                cursors.remove(&package_id);
+                debug!(package_id = %package_id, "Saved event cursor");
+            }
+            Err(e) => warn!(package_id = %package_id, error = %e, "Failed to save event cursor"),
+        }
+    }
+}
+
+async fn save_dead_letter(
+    db: &Database,
+    event: SuiEvent,
+    package_version: u64,
+    attempts: u32,
+    last_error: String,
+) {
+    metrics::DEAD_LETTERED_EVENTS.inc();
+    let dead_letter = match DeadLetter::new(&event, package_version, attempts, last_error) {
+        Ok(dead_letter) => dead_letter,
+        Err(e) => {
+            error!(event = ?event, error = ?e, "Failed to serialize event");
+            return;
+        }
+    };
+    let id = dead_letter.id();
+    let event = dead_letter.event.clone();
+    match db.save_dead_letter(dead_letter).await {
+        Ok(()) => error!(id = %id, "Event moved to dead letters"),
+        // 저장하지 못하면 로그에라도 원본을 남깁니다.
+        Err(e) => error!(id = %id, error = ?e, event = %event, "Failed to save dead letter"),
+    }
+}
+
+/// 저장된 dead letter를 다시 처리합니다. 성공한 항목은 삭제하고, 실패하면 기록을 갱신합니다.
+pub async fn reprocess_dead_letters(pipeline: EventPipeline) -> Result<()> {
+    let db = pipeline.db.clone();
+    let dead_letters = db.get_dead_letters().await?;
+    info!(count = dead_letters.len(), "Reprocessing dead letters");
+
+    let mut reprocessed = 0;
+    let mut unreadable = 0;
+    for mut dead_letter in dead_letters {
+        let id = dead_letter.id();
+        // 읽을 수 없는 항목은 그대로 두고 나머지를 계속 처리합니다.
+        let event = match dead_letter.event() {
+            Ok(event) => event,
+            Err(e) => {
+                error!(id = %id, error = ?e, "Failed to read dead letter");
+                unreadable += 1;
+                continue;
             }
-            _ => {
-                eprintln!("Unknown Event: {}", event_name);
+        };
+        let ctx = pipeline.context(dead_letter.package_version);
+        match handle_event(&pipeline.handlers, ctx, event).await {
+            Ok(()) => {
+                db.delete_dead_letter(&id).await?;
+                reprocessed += 1;
+                info!(id = %id, "Reprocessed dead letter");
+            }
+            Err(e) => {
+                warn!(id = %id, kind = e.kind(), error = %e, "Dead letter failed again");
+                dead_letter.failed_again(e.to_string());
+                db.save_dead_letter(dead_letter).await?;
             }
         }
     }
 
-    info!("Channel Closed, exiting loop");
+    info!(
+        count = reprocessed,
+        unreadable, "Finished reprocessing dead letters"
+    );
     Ok(())
 }
 
+/// 이벤트 타입에 등록된 처리기를 호출합니다.
+///
+/// 이벤트마다 span을 열어 처리 중 남기는 로그에 트랜잭션과 풀 정보가 함께 남도록 합니다.
+async fn handle_event(
+    handlers: &HandlerRegistry,
+    ctx: HandlerContext<'_>,
+    event: SuiEvent,
+) -> Result<(), Error> {
+    let Some(handler) = handlers.resolve(&event) else {
+        warn!(event_type = %event.type_, "No handler for event");
+        return Ok(());
+    };
+    let event_name = event.type_.name.to_string();
+    let span = info_span!(
+        "event",
+        tx_digest = %event.id.tx_digest,
+        event_seq = event.id.event_seq,
+        event_type = %event_name,
+        package_version = ctx.package_version,
+        coin_type = field::Empty,
+        pool_id = field::Empty,
+    );
+    let timer = metrics::HANDLER_DURATION
+        .with_label_values(&[&event_name])
+        .start_timer();
+    let result = async {
+        debug!("Handling event");
+        handler.handle(&ctx, event).await
+    }
+    .instrument(span)
+    .await;
+    timer.observe_duration();
+
+    let outcome = match &result {
+        Ok(()) => "ok",
+        Err(e) => e.kind(),
+    };
+    metrics::EVENTS_PROCESSED
+        .with_label_values(&[&event_name, outcome])
+        .inc();
+    result
+}
+
 /// 스왑 이벤트 제어 함수
 pub async fn control_swap_event(
-    sui: Arc<SuiClient>,
-    db: Arc<Database>,
+    ctx: &HandlerContext<'_>,
+    swap: MoveSwapEvent,
     event: SuiEvent,
-) -> Result<()> {
-    if let Ok(mut swap_event) = serde_json::from_value::<SwapEvent>(event.parsed_json) {
-        let coin_type = get_coin_type_by_pool_id(sui.clone(), swap_event.pool_id.clone()).await?;
-        let account_meme_balance = sui
-            .coin_read_api()
-            .get_balance(
-                SuiAddress::from_str(&swap_event.account)?,
-                Some(coin_type.clone()),
-            )
-            .await?
-            .total_balance;
-        swap_event.account_meme_balance = Some(account_meme_balance as u64);
-        swap_event.coin_type = Some(coin_type.clone());
-        swap_event.timestamp = event.timestamp_ms.clone();
-        // info!(
-        //     "rounded timestamp = {:?} , now_timestamp = {:?}",
-        //     convert_chart_timestamp(event.timestamp_ms.unwrap()),
-        //     event.timestamp_ms.unwrap()
-        // );
-        swap_event.digest = Some(event.id.tx_digest.to_string());
-
-        //@@ price 구하는 방법은?
-
-        let reserve_meme = Decimal::from_str(&swap_event.reserve_meme)?;
-        let reserve_sui = Decimal::from_str(&swap_event.reserve_sui)?;
-        let price = reserve_sui / reserve_meme;
-        info!("Price is ={:?}", price);
-        swap_event.current_price = Some(price);
-        db.update_pool_info_reserve(swap_event.clone()).await?;
-        db.save_trade_data(swap_event.clone()).await?;
-        db.save_chart_data(swap_event.clone()).await?;
-        db.update_token_recent_trade(coin_type, event.timestamp_ms.unwrap())
-            .await?;
-    } else {
-        eprintln!("Failed to parse SwapEvent data");
+) -> Result<(), Error> {
+    let (db, update_sender) = (&ctx.db, ctx.update_sender);
+    let timestamp = event.timestamp_ms.or_missing("timestamp_ms")?;
+    let mut swap_event = SwapEvent::new(swap);
+    Span::current().record("pool_id", field::display(swap_event.pool_id));
+    let coin_type = ctx
+        .coin_types
+        .get(ctx.sui.clone(), swap_event.pool_id)
+        .await?;
+    Span::current().record("coin_type", coin_type.as_str());
+    let account_meme_balance =
+        account_balance_after(ctx, swap_event.account, &coin_type, &event).await?;
+    swap_event.account_meme_balance = Some(account_meme_balance);
+    swap_event.coin_type = Some(coin_type.clone());
+    swap_event.timestamp = Some(timestamp);
+    swap_event.digest = Some(event.id.tx_digest.to_string());
+    swap_event.event_seq = Some(event.id.event_seq);
+    swap_event.package_version = Some(ctx.package_version);
+
+    //@@ price 구하는 방법은?
+
+    let reserve_meme = Decimal::from(swap_event.reserve_meme);
+    let reserve_sui = Decimal::from(swap_event.reserve_sui);
+    let price = reserve_sui
+        .checked_div(reserve_meme)
+        .ok_or_else(|| Error::parse("reserve_meme", "must not be zero"))?;
+    debug!(price = %price, "Price updated");
+    swap_event.current_price = Some(price);
+    // 같은 이벤트를 다시 처리해도 결과가 같도록 reserve는 시각으로 비교하고, 차트는 거래를 저장하기 전에 반영합니다.
+    let pool_info = db.update_pool_info_reserve(swap_event.clone()).await?;
+    let trade = save_trade_and_chart(db, swap_event).await?;
+    db.update_token_recent_trade(coin_type.clone(), timestamp)
+        .await?;
+
+    // 구독자가 없으면 send가 실패하므로 결과는 무시합니다.
+    if let Some((trade, chart)) = trade {
+        let _ = update_sender.send(MarketUpdate::Trade {
+            coin_type: coin_type.clone(),
+            trade,
+        });
+        let _ = update_sender.send(MarketUpdate::Chart {
+            coin_type: coin_type.clone(),
+            chart,
+        });
     }
+    let _ = update_sender.send(MarketUpdate::PoolInfo {
+        coin_type,
+        pool_info,
+    });
     Ok(())
 }
 
+/// 거래를 차트에 반영하고 저장합니다. 이미 저장된 거래면 아무것도 바꾸지 않고 None을 반환합니다.
+///
+/// 차트를 반영한 뒤 거래 저장에 실패해 다시 처리하면 같은 가격과 시각을 한 번 더 반영하므로 캔들은 그대로입니다.
+async fn save_trade_and_chart(
+    db: &Database,
+    swap_event: SwapEvent,
+) -> Result<Option<(Trade, Chart)>, Error> {
+    let coin_type = swap_event.coin_type.as_deref().or_missing("coin_type")?;
+    let digest = swap_event.digest.as_deref().or_missing("digest")?;
+    let event_seq = swap_event.event_seq.or_missing("event_seq")?;
+    if db.trade_exists(coin_type, digest, event_seq).await? {
+        debug!("Trade already saved");
+        return Ok(None);
+    }
+    let chart = db.save_chart_data(swap_event.clone()).await?;
+    Ok(db
+        .save_trade_data(swap_event)
+        .await?
+        .map(|trade| (trade, chart)))
+}
+
 /// 풀 생성 이벤트 제어 함수
 pub async fn create_pool_event(
-    sui: Arc<SuiClient>,
-    db: Arc<Database>,
+    ctx: &HandlerContext<'_>,
+    create_pool: MoveCreatePoolEvent,
     event: SuiEvent,
-) -> Result<()> {
-    info!("Create Pool Event!! \n\n");
-    if let Ok(mut create_pool_event) = serde_json::from_value::<CreatePoolEvent>(event.parsed_json)
-    {
-        let coin_type =
-            get_coin_type_by_pool_id(sui.clone(), create_pool_event.pool_id.clone()).await?;
+) -> Result<(), Error> {
+    let (sui, db) = (ctx.sui.clone(), &ctx.db);
+    let timestamp = event.timestamp_ms.or_missing("timestamp_ms")?;
+    let mut create_pool_event = CreatePoolEvent::new(create_pool);
+    Span::current().record("pool_id", field::display(create_pool_event.pool_id));
+    let coin_type = ctx
+        .coin_types
+        .get(sui.clone(), create_pool_event.pool_id)
+        .await?;
+    Span::current().record("coin_type", coin_type.as_str());
 
-        let coin_metadata = sui
-            .coin_read_api()
-            .get_coin_metadata(coin_type.to_string())
-            .await?;
+    let coin_metadata = metrics::rpc(
+        "get_coin_metadata",
+        sui.coin_read_api().get_coin_metadata(coin_type.to_string()),
+    )
+    .await?
+    .or_missing("coin metadata")?;
 
-        let total_supply = sui
-            .coin_read_api()
-            .get_total_supply(coin_type.to_string())
-            .await?
-            .value;
+    let total_supply = metrics::rpc(
+        "get_total_supply",
+        sui.coin_read_api().get_total_supply(coin_type.to_string()),
+    )
+    .await?
+    .value;
 
-        create_pool_event.coin_type = Some(coin_type.to_string());
-        create_pool_event.timestamp = event.timestamp_ms;
-        create_pool_event.digest = Some(event.id.tx_digest.to_string());
+    create_pool_event.coin_type = Some(coin_type.to_string());
+    create_pool_event.timestamp = Some(timestamp);
+    create_pool_event.digest = Some(event.id.tx_digest.to_string());
+    create_pool_event.package_version = Some(ctx.package_version);
 
-        db.save_pool(create_pool_event.clone()).await?;
-        db.save_token(
+    let pool_info = db.save_pool(create_pool_event.clone()).await?;
+    let token = db
+        .save_token(
             create_pool_event,
-            coin_metadata.unwrap(),
+            coin_metadata,
             coin_type.to_string(),
             total_supply,
         )
         .await?;
-    } else {
-        eprintln!("Failed to parse CreatePoolEvent data");
-    }
+
+    info!("Pool created");
+    let _ = ctx.update_sender.send(MarketUpdate::Launch {
+        coin_type,
+        token,
+        pool_info,
+    });
     Ok(())
 }
 
-async fn get_coin_type_by_pool_id(sui: Arc<SuiClient>, pool_id: String) -> Result<String> {
-    let pool_type = sui
-        .read_api()
-        .get_object_with_options(
-            ObjectID::from_str(&pool_id)?,
-            SuiObjectDataOptions::new().with_type(),
+/// 유동성 공급/회수 이벤트 제어 함수
+///
+/// reserve와 계정별 기록을 갱신하고 차트에는 가격을 바꾸지 않고 유동성 변화만 표시합니다.
+pub async fn liquidity_event(
+    ctx: &HandlerContext<'_>,
+    liquidity: MoveLiquidityEvent,
+    action_type: LiquidityActionType,
+    event: SuiEvent,
+) -> Result<(), Error> {
+    let (db, update_sender) = (&ctx.db, ctx.update_sender);
+    let package_version = ctx.package_version;
+    let timestamp = event.timestamp_ms.or_missing("timestamp_ms")?;
+    Span::current().record("pool_id", field::display(liquidity.pool_id));
+    let coin_type = ctx
+        .coin_types
+        .get(ctx.sui.clone(), liquidity.pool_id)
+        .await?;
+    Span::current().record("coin_type", coin_type.as_str());
+
+    // 계정 잔고는 잔고 변화를 누적하므로 스왑이 아닌 잔고 변화도 반영합니다.
+    account_balance_after(ctx, liquidity.account, &coin_type, &event).await?;
+    let pool_info = db
+        .update_pool_reserve(
+            coin_type.clone(),
+            liquidity.pool_id.to_string(),
+            liquidity.reserve_meme,
+            liquidity.reserve_sui,
+            timestamp,
+            Some(package_version),
         )
+        .await?;
+    let action = LiquidityAction::new(
+        &liquidity,
+        action_type,
+        coin_type.clone(),
+        timestamp,
+        &event.id,
+        package_version,
+    );
+    // 차트의 유동성 변화 횟수는 누적되므로 이미 저장한 기록이면 다시 세지 않습니다.
+    if db.liquidity_action_exists(&action.id()).await? {
+        debug!("Liquidity action already saved");
+        let _ = update_sender.send(MarketUpdate::PoolInfo {
+            coin_type,
+            pool_info,
+        });
+        return Ok(());
+    }
+
+    // 유동성을 모두 회수한 풀은 가격이 없으므로 0으로 둡니다. 차트가 비어 있을 때만 쓰입니다.
+    let price = Decimal::from(liquidity.reserve_sui)
+        .checked_div(Decimal::from(liquidity.reserve_meme))
+        .unwrap_or(Decimal::ZERO);
+    let chart = db
+        .mark_chart_liquidity(&coin_type, timestamp, price)
+        .await?;
+    db.save_liquidity_action(action.clone()).await?;
+
+    info!(action = ?action_type, "Liquidity changed");
+    let _ = update_sender.send(MarketUpdate::Liquidity {
+        coin_type: coin_type.clone(),
+        action,
+    });
+    let _ = update_sender.send(MarketUpdate::Chart {
+        coin_type: coin_type.clone(),
+        chart,
+    });
+    let _ = update_sender.send(MarketUpdate::PoolInfo {
+        coin_type,
+        pool_info,
+    });
+    Ok(())
+}
+
+/// 풀 상태(졸업, DEX 이전) 이벤트 제어 함수
+pub async fn lifecycle_event(
+    ctx: &HandlerContext<'_>,
+    pool_id: ObjectID,
+    lifecycle: Lifecycle,
+) -> Result<(), Error> {
+    Span::current().record("pool_id", field::display(pool_id));
+    let coin_type = ctx.coin_types.get(ctx.sui.clone(), pool_id).await?;
+    Span::current().record("coin_type", coin_type.as_str());
+
+    let lifecycle = ctx.db.advance_lifecycle(&coin_type, lifecycle).await?;
+    info!(lifecycle = ?lifecycle, "Pool lifecycle changed");
+    ctx.migration_targets
+        .insert(&lifecycle, coin_type.clone())
+        .await;
+    let _ = ctx.update_sender.send(MarketUpdate::Lifecycle {
+        coin_type,
+        lifecycle,
+    });
+    Ok(())
+}
+
+/// 외부 DEX 스왑 이벤트 제어 함수
+///
+/// AMM에서 옮겨간 풀(`coin_type`의 이전 대상)의 거래를 거래와 차트에 반영합니다. AMM 풀의 reserve는 건드리지 않습니다.
+pub async fn dex_swap_event(
+    ctx: &HandlerContext<'_>,
+    swap: DexSwap,
+    coin_type: String,
+    (coin_a, coin_b): (TypeTag, TypeTag),
+    event: SuiEvent,
+) -> Result<(), Error> {
+    let (db, update_sender) = (&ctx.db, ctx.update_sender);
+    let timestamp = event.timestamp_ms.or_missing("timestamp_ms")?;
+    Span::current().record("pool_id", field::display(swap.pool_id));
+    Span::current().record("coin_type", coin_type.as_str());
+    // 풀 객체 타입 문자열에서 얻은 AMM 코인 타입과 같은 (주소 전체를 쓰는) 형식으로 비교합니다.
+    let sui_type = GAS::type_tag();
+    let meme_is_a = if coin_b == sui_type && coin_a.to_canonical_string(true) == coin_type {
+        true
+    } else if coin_a == sui_type && coin_b.to_canonical_string(true) == coin_type {
+        false
+    } else {
+        return Err(Error::parse(
+            "migration target pool",
+            format!(
+                "expected {} and SUI, got {} and {}",
+                coin_type, coin_a, coin_b
+            ),
+        ));
+    };
+
+    let mut swap_event = SwapEvent {
+        account: swap.account,
+        pool_id: swap.pool_id,
+        meme_in_amount: 0,
+        meme_out_amount: 0,
+        sui_in_amount: 0,
+        sui_out_amount: 0,
+        reserve_meme: 0,
+        reserve_sui: 0,
+        timestamp: Some(timestamp),
+        coin_type: Some(coin_type.clone()),
+        account_meme_balance: None,
+        digest: Some(event.id.tx_digest.to_string()),
+        event_seq: Some(event.id.event_seq),
+        current_price: None,
+        package_version: None,
+        venue: Some(swap.venue.to_string()),
+    };
+    // 밈 코인을 내고 SUI를 받았으면 매도입니다.
+    let (meme_amount, sui_amount) = if swap.a_to_b == meme_is_a {
+        swap_event.meme_in_amount = swap.amount_in;
+        swap_event.sui_out_amount = swap.amount_out;
+        (swap.amount_in, swap.amount_out)
+    } else {
+        swap_event.sui_in_amount = swap.amount_in;
+        swap_event.meme_out_amount = swap.amount_out;
+        (swap.amount_out, swap.amount_in)
+    };
+    let price = Decimal::from(sui_amount)
+        .checked_div(Decimal::from(meme_amount))
+        .ok_or_else(|| Error::parse("meme amount", "must not be zero"))?;
+    debug!(venue = %swap.venue, price = %price, "External swap");
+    swap_event.current_price = Some(price);
+    let account_meme_balance = account_balance_after(ctx, swap.account, &coin_type, &event).await?;
+    swap_event.account_meme_balance = Some(account_meme_balance);
+
+    let Some((trade, chart)) = save_trade_and_chart(db, swap_event).await? else {
+        return Ok(());
+    };
+    db.update_token_recent_trade(coin_type.clone(), timestamp)
+        .await?;
+
+    let _ = update_sender.send(MarketUpdate::Trade {
+        coin_type: coin_type.clone(),
+        trade,
+    });
+    let _ = update_sender.send(MarketUpdate::Chart { coin_type, chart });
+    Ok(())
+}
+
+/// 이벤트가 속한 트랜잭션 직후 계정의 코인 잔고
+///
+/// 계정마다 트랜잭션의 잔고 변화를 (체크포인트 시각, 트랜잭션 버전) 순서로 기록하고 0에서 시작해 그 순서대로 더한
+/// 값을 잔고로 봅니다. backfill이나 재시도로 늦게 처리한 트랜잭션은 순서에 맞게 끼워 넣고, 뒤의 트랜잭션의 잔고와
+/// 거래 기록을 고칩니다. AMM과 DEX 처리 태스크가 같은 계정을 함께 고치지 않도록 계정을 잠근 채 갱신합니다.
+#[instrument(skip(ctx, event))]
+async fn account_balance_after(
+    ctx: &HandlerContext<'_>,
+    account: SuiAddress,
+    coin_type: &str,
+    event: &SuiEvent,
+) -> Result<u64, Error> {
+    let (sui, db) = (&ctx.sui, &ctx.db);
+    let account_str = account.to_string();
+    let digest = event.id.tx_digest.to_string();
+    let _lock = ctx
+        .account_locks
+        .lock(AccountBalance::id(coin_type, &account_str))
+        .await;
+    if let Some(change) = db
+        .get_balance_change(&digest, coin_type, &account_str)
         .await?
-        .data
-        .unwrap()
-        .object_type()?
+    {
+        return Ok(change.clamped_balance());
+    }
+
+    let response = metrics::rpc(
+        "get_transaction_block",
+        sui.read_api().get_transaction_with_options(
+            event.id.tx_digest,
+            SuiTransactionBlockResponseOptions::new()
+                .with_balance_changes()
+                .with_effects(),
+        ),
+    )
+    .await?;
+    // 트랜잭션이 바꾼 객체는 모두 같은 버전을 받으므로 가스 객체의 버전을 트랜잭션의 버전으로 씁니다.
+    let version = response
+        .effects
+        .as_ref()
+        .or_missing("effects")?
+        .gas_object()
+        .reference
+        .version
+        .value();
+    let balance_changes = response.balance_changes.or_missing("balance changes")?;
+    // 주소 표기 형식에 관계없이 비교하도록 타입으로 바꿉니다.
+    let coin_type_tag = parse_sui_type_tag(coin_type).map_err(|e| Error::parse("coin type", e))?;
+    let amount: i128 = balance_changes
+        .iter()
+        .filter(|change| matches!(change.owner, Owner::AddressOwner(owner) if owner == account))
+        .filter(|change| change.coin_type == coin_type_tag)
+        .map(|change| change.amount)
+        .sum();
+
+    let (change, shifted) = db
+        .insert_balance_change(BalanceChange {
+            account: account_str.clone(),
+            coin_type: coin_type.to_string(),
+            transaction_hash: digest,
+            amount: i64::try_from(amount).map_err(|e| Error::parse("balance change", e))?,
+            balance: 0,
+            timestamp: event.timestamp_ms.or_missing("timestamp_ms")?,
+            version,
+        })
+        .await?;
+    if change.balance < 0 {
+        // 인덱싱하지 않은 전송으로 받은 코인을 내보냈으므로 잔고는 0으로 봅니다.
+        metrics::NEGATIVE_BALANCES.inc();
+        warn!(
+            balance = change.balance,
+            amount = change.amount,
+            "Balance went negative"
+        );
+    }
+    if !shifted.is_empty() {
+        debug!(count = shifted.len(), "Shifted later balance changes");
+        let balances = shifted
+            .iter()
+            .map(|change| (change.transaction_hash.clone(), change.clamped_balance()))
+            .collect();
+        // 잔고 기록은 이미 맞으므로 거래 기록을 고치지 못해도 이벤트는 처리한 것으로 봅니다.
+        if let Err(e) = db
+            .update_trade_balances(coin_type, &account_str, &balances)
+            .await
+        {
+            warn!(error = %e, "Failed to update balances of later trades");
+        }
+    }
+    Ok(change.clamped_balance())
+}
+
+/// 계정·코인별 잠금
+///
+/// 잔고 기록을 읽고 고치는 동안 잡아 여러 처리 태스크가 같은 계정의 기록을 함께 고치지 않게 합니다.
+#[derive(Default)]
+pub struct AccountLocks {
+    locks: std::sync::Mutex<HashMap<String, Arc<Mutex<()>>>>,
+}
+
+impl AccountLocks {
+    pub async fn lock(&self, key: String) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap();
+            // 아무도 잡거나 기다리지 않는 잠금은 정리합니다.
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+            locks.entry(key).or_default().clone()
+        };
+        lock.lock_owned().await
+    }
+}
+
+/// 외부 DEX 풀 객체 타입의 앞 두 타입 인자를 (a, b) 코인 타입으로 봅니다.
+#[instrument(skip(sui))]
+pub async fn get_pool_coins(
+    sui: Arc<SuiClient>,
+    pool_id: ObjectID,
+) -> Result<(TypeTag, TypeTag), Error> {
+    let pool_type = metrics::rpc(
+        "get_object",
+        sui.read_api()
+            .get_object_with_options(pool_id, SuiObjectDataOptions::new().with_type()),
+    )
+    .await?
+    .data
+    .or_missing("pool object")?
+    .object_type()
+    .map_err(|e| Error::parse("pool object type", e))?
+    .to_string();
+    let pool_type =
+        parse_sui_struct_tag(&pool_type).map_err(|e| Error::parse("pool object type", e))?;
+    match pool_type.type_params.as_slice() {
+        [coin_a, coin_b, ..] => Ok((coin_a.clone(), coin_b.clone())),
+        _ => Err(Error::parse(
+            "pool object type",
+            format!("expected coin type params, got {}", pool_type),
+        )),
+    }
+}
+
+/// AMM 풀 id별 밈 코인 타입
+///
+/// 풀의 코인 타입은 바뀌지 않으므로 저장된 풀로 채워 두고, 처음 보는 풀만 풀 객체를 조회합니다.
+#[derive(Default)]
+pub struct CoinTypeCache {
+    coin_types: RwLock<HashMap<ObjectID, String>>,
+}
+
+impl CoinTypeCache {
+    /// 저장된 모든 풀의 코인 타입으로 채웁니다.
+    pub async fn load(db: &Database) -> Result<Self> {
+        let mut coin_types = HashMap::new();
+        for pool_info in db.get_pool_infos().await? {
+            match pool_info.pool_id.parse::<ObjectID>() {
+                Ok(pool_id) => {
+                    coin_types.insert(pool_id, pool_info.coin_type);
+                }
+                Err(e) => {
+                    warn!(pool_id = %pool_info.pool_id, error = %e, "Skipping pool with invalid id")
+                }
+            }
+        }
+        info!(count = coin_types.len(), "Loaded pool coin types");
+        Ok(CoinTypeCache {
+            coin_types: RwLock::new(coin_types),
+        })
+    }
+
+    pub async fn get(&self, sui: Arc<SuiClient>, pool_id: ObjectID) -> Result<String, Error> {
+        if let Some(coin_type) = self.coin_types.read().await.get(&pool_id) {
+            return Ok(coin_type.clone());
+        }
+        let coin_type = get_coin_type_by_pool_id(sui, pool_id).await?;
+        self.coin_types
+            .write()
+            .await
+            .insert(pool_id, coin_type.clone());
+        Ok(coin_type)
+    }
+}
+
+/// 외부 DEX로 옮겨간 풀의 (이전한 DEX 풀 id별) 밈 코인 타입
+///
+/// 외부 DEX 이벤트 중 이 풀들의 스왑만 우리 토큰의 거래로 기록합니다. 저장된 풀로 채우고 이전 이벤트를 처리할 때 추가합니다.
+#[derive(Default)]
+pub struct MigrationTargets {
+    coin_types: RwLock<HashMap<ObjectID, String>>,
+}
+
+impl MigrationTargets {
+    /// 외부 DEX로 옮겨간 저장된 풀로 채웁니다.
+    pub async fn load(db: &Database) -> Result<Self> {
+        let targets = MigrationTargets::default();
+        for pool_info in db.get_pool_infos().await? {
+            targets
+                .insert(&pool_info.lifecycle, pool_info.coin_type)
+                .await;
+        }
+        info!(
+            count = targets.coin_types.read().await.len(),
+            "Loaded migration target pools"
+        );
+        Ok(targets)
+    }
+
+    /// 옮겨간 풀이면 이전한 DEX 풀을 추가합니다.
+    pub async fn insert(&self, lifecycle: &Lifecycle, coin_type: String) {
+        let Lifecycle::Migrated { target_pool_id, .. } = lifecycle else {
+            return;
+        };
+        match target_pool_id.parse::<ObjectID>() {
+            Ok(pool_id) => {
+                self.coin_types.write().await.insert(pool_id, coin_type);
+            }
+            Err(e) => warn!(
+                target_pool_id = %target_pool_id,
+                error = %e,
+                "Skipping migration target with invalid id"
+            ),
+        }
+    }
+
+    pub async fn get(&self, pool_id: &ObjectID) -> Option<String> {
+        self.coin_types.read().await.get(pool_id).cloned()
+    }
+}
+
+#[instrument(skip(sui))]
+async fn get_coin_type_by_pool_id(sui: Arc<SuiClient>, pool_id: ObjectID) -> Result<String, Error> {
+    let pool_type = metrics::rpc(
+        "get_object",
+        sui.read_api()
+            .get_object_with_options(pool_id, SuiObjectDataOptions::new().with_type()),
+    )
+    .await?
+    .data
+    .or_missing("pool object")?
+    .object_type()
+    .map_err(|e| Error::parse("pool object type", e))?
+    .to_string();
+    let coin_type = POOL_TYPE
+        .captures(&pool_type)
+        .and_then(|captures| captures.get(1))
+        .or_missing("coin type in pool object type")?
+        .as_str()
         .to_string();
-    let re = Regex::new(r"::Pool<([^>]+)>")?;
-    let captures = re.captures(&pool_type).unwrap();
-    let coin_type = captures.get(1).unwrap().as_str().to_string();
     Ok(coin_type)
 }
@@ -0,0 +1,139 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use serde_json::Value;
+use sui_sdk::{
+    rpc_types::{SuiObjectDataOptions, SuiParsedData},
+    SuiClient,
+};
+use tokio::sync::broadcast::Sender;
+use tracing::{info, instrument, warn};
+
+use crate::{
+    db::{
+        model::{Lifecycle, MarketUpdate, PoolInfo},
+        Database,
+    },
+    error::{parse_field, Error, OrMissing},
+    metrics,
+    shutdown::{self, Shutdown},
+    sui::SuiClientPool,
+};
+
+/// 풀 객체에서 reserve를 담은 필드 이름
+const RESERVE_MEME_FIELD: &str = "reserve_meme";
+const RESERVE_SUI_FIELD: &str = "reserve_sui";
+
+/// 종료 요청 전까지 주기적으로 저장된 풀 reserve를 체인의 풀 객체와 맞춥니다.
+///
+/// 이벤트를 놓쳐 어긋난 reserve를 바로잡는 안전장치입니다. 처리가 밀려 일시적으로 달라 보이는 풀도
+/// 보정되지만, 뒤따르는 이벤트가 같은 값을 다시 쓰므로 결과는 같습니다.
+pub async fn run_reconciler(
+    pool: Arc<SuiClientPool>,
+    db: Arc<Database>,
+    update_sender: Sender<MarketUpdate>,
+    interval: Duration,
+    mut shutdown: Shutdown,
+) -> Result<()> {
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                // 한 번 실패해도 태스크를 재시작하지 않고 다음 주기에 다시 시도합니다.
+                if let Err(e) = reconcile_pools(pool.client(), &db, &update_sender).await {
+                    warn!(error = ?e, "Failed to reconcile pools");
+                }
+            }
+            _ = shutdown::requested(&mut shutdown) => return Ok(()),
+        }
+    }
+}
+
+/// 모든 풀을 한 번 비교합니다. 풀 하나를 보정하지 못해도 나머지는 계속 진행합니다.
+#[instrument(skip_all)]
+pub async fn reconcile_pools(
+    sui: Arc<SuiClient>,
+    db: &Database,
+    update_sender: &Sender<MarketUpdate>,
+) -> Result<()> {
+    let pools = db.get_pool_infos().await?;
+    let mut corrected = 0;
+    for pool_info in pools {
+        // 외부 DEX로 옮겨간 풀은 비어 있거나 삭제되었으므로 건너뜁니다.
+        if matches!(pool_info.lifecycle, Lifecycle::Migrated { .. }) {
+            continue;
+        }
+        let coin_type = pool_info.coin_type.clone();
+        match reconcile_pool(sui.clone(), db, update_sender, pool_info).await {
+            Ok(true) => corrected += 1,
+            Ok(false) => {}
+            Err(e) => {
+                metrics::POOL_RECONCILE_ERRORS.inc();
+                warn!(coin_type = %coin_type, kind = e.kind(), error = %e, "Failed to reconcile pool");
+            }
+        }
+    }
+    info!(corrected, "Reconciled pools");
+    Ok(())
+}
+
+/// 체인의 reserve와 다르면 저장된 값을 고치고 true를 반환합니다.
+async fn reconcile_pool(
+    sui: Arc<SuiClient>,
+    db: &Database,
+    update_sender: &Sender<MarketUpdate>,
+    stored: PoolInfo,
+) -> Result<bool, Error> {
+    let pool_id = parse_field(&stored.pool_id, "pool_id")?;
+    let data = metrics::rpc(
+        "get_object",
+        sui.read_api()
+            .get_object_with_options(pool_id, SuiObjectDataOptions::new().with_content()),
+    )
+    .await?
+    .data
+    .or_missing("pool object")?;
+    let Some(SuiParsedData::MoveObject(object)) = data.content else {
+        return Err(Error::Missing("pool object content"));
+    };
+    let fields = object.fields.to_json_value();
+    let reserve_meme = reserve(&fields, RESERVE_MEME_FIELD)?;
+    let reserve_sui = reserve(&fields, RESERVE_SUI_FIELD)?;
+    if (stored.reserve_meme, stored.reserve_sui) == (reserve_meme, reserve_sui) {
+        return Ok(false);
+    }
+
+    metrics::POOL_DRIFTS.inc();
+    warn!(
+        coin_type = %stored.coin_type,
+        pool_id = %stored.pool_id,
+        stored_meme = stored.reserve_meme,
+        stored_sui = stored.reserve_sui,
+        chain_meme = reserve_meme,
+        chain_sui = reserve_sui,
+        "Pool reserves drifted from chain, correcting"
+    );
+    let Some(pool_info) = db
+        .set_pool_reserve(&stored, reserve_meme, reserve_sui)
+        .await?
+    else {
+        info!(coin_type = %stored.coin_type, "Pool reserves changed while reconciling, skipping");
+        return Ok(false);
+    };
+    let _ = update_sender.send(MarketUpdate::PoolInfo {
+        coin_type: stored.coin_type,
+        pool_info,
+    });
+    Ok(true)
+}
+
+/// `Balance`는 문자열 값으로 보이지만 `{ value }` 구조체로 보이는 경우도 받아들입니다.
+fn reserve(fields: &Value, field: &'static str) -> Result<u64, Error> {
+    let value = fields.get(field).or_missing(field)?;
+    let value = value.get("value").unwrap_or(value);
+    match value {
+        Value::String(amount) => parse_field(amount, field),
+        Value::Number(amount) => amount.as_u64().or_missing(field),
+        _ => Err(Error::parse(field, format!("unexpected value {}", value))),
+    }
+}
@@ -0,0 +1,123 @@
+use sui_sdk::rpc_types::SuiEvent;
+use tokio::time::Instant;
+
+use crate::config::RetryConfig;
+
+/// 처리에 실패해 다시 시도할 이벤트
+#[derive(Debug)]
+pub struct PendingRetry {
+    pub event: SuiEvent,
+    pub package_version: u64,
+    /// 지금까지 실패한 횟수
+    pub attempts: u32,
+    pub last_error: String,
+    due: Instant,
+}
+
+impl PendingRetry {
+    /// 다시 시도하지 않고 바로 dead letter로 보낼 이벤트
+    pub fn failed(
+        event: SuiEvent,
+        package_version: u64,
+        attempts: u32,
+        last_error: String,
+    ) -> Self {
+        PendingRetry {
+            event,
+            package_version,
+            attempts,
+            last_error,
+            due: Instant::now(),
+        }
+    }
+}
+
+/// 실패한 이벤트를 backoff 후 다시 처리하기 위한 대기열
+#[derive(Debug)]
+pub struct RetryQueue {
+    config: RetryConfig,
+    pending: Vec<PendingRetry>,
+}
+
+impl RetryQueue {
+    pub fn new(config: RetryConfig) -> Self {
+        RetryQueue {
+            config,
+            pending: Vec::new(),
+        }
+    }
+
+    /// 실패한 이벤트를 다시 시도하도록 예약합니다.
+    /// 시도 횟수를 모두 쓴 이벤트는 예약하지 않고 돌려줍니다.
+    pub fn schedule(
+        &mut self,
+        event: SuiEvent,
+        package_version: u64,
+        attempts: u32,
+        last_error: String,
+    ) -> Option<PendingRetry> {
+        let backoff = self
+            .config
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempts.saturating_sub(1)))
+            .min(self.config.max_backoff);
+        let retry = PendingRetry {
+            event,
+            package_version,
+            attempts,
+            last_error,
+            due: Instant::now() + backoff,
+        };
+        if attempts >= self.config.max_attempts {
+            return Some(retry);
+        }
+        self.pending.push(retry);
+        None
+    }
+
+    /// 이전 실행에서 저장한 재시도를 바로 시도하도록 되돌립니다.
+    pub fn restore(
+        &mut self,
+        event: SuiEvent,
+        package_version: u64,
+        attempts: u32,
+        last_error: String,
+    ) {
+        self.pending.push(PendingRetry::failed(
+            event,
+            package_version,
+            attempts,
+            last_error,
+        ));
+    }
+
+    /// 가장 먼저 시도할 이벤트의 시각까지 기다린 뒤 꺼냅니다. 비어 있으면 계속 기다립니다.
+    ///
+    /// 기다리는 도중 취소되어도 대기열은 바뀌지 않으므로 `select!`에서 사용할 수 있습니다.
+    pub async fn next_due(&mut self) -> PendingRetry {
+        let Some((index, due)) = self
+            .pending
+            .iter()
+            .enumerate()
+            .map(|(index, retry)| (index, retry.due))
+            .min_by_key(|(_, due)| *due)
+        else {
+            return std::future::pending().await;
+        };
+        tokio::time::sleep_until(due).await;
+        self.pending.swap_remove(index)
+    }
+
+    /// 남아 있는 모든 이벤트를 꺼냅니다.
+    pub fn drain(&mut self) -> Vec<PendingRetry> {
+        std::mem::take(&mut self.pending)
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
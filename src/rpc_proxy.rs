@@ -0,0 +1,190 @@
+//! RPC 요청에 설정한 헤더를 붙여 전달하는 로컬 프록시
+//!
+//! `SuiClientBuilder`는 basic auth 외의 요청 헤더를 설정할 수 없으므로, 헤더가 필요한 엔드포인트는
+//! 127.0.0.1의 프록시를 거쳐 연결합니다. HTTP 요청과 WebSocket 연결을 모두 전달합니다.
+
+use std::{net::TcpListener, sync::Arc};
+
+use anyhow::{Context, Result};
+use axum::{
+    body::Bytes,
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
+    response::{IntoResponse, Response},
+    Router,
+};
+use futures_util::{SinkExt, StreamExt};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{self, client::IntoClientRequest},
+};
+use tracing::{info, warn};
+
+use crate::config::RpcEndpoint;
+
+/// 연결마다 다시 만들어지므로 노드로 전달하지 않는 헤더
+static HOP_BY_HOP: [HeaderName; 9] = [
+    header::HOST,
+    header::CONNECTION,
+    header::CONTENT_LENGTH,
+    header::TRANSFER_ENCODING,
+    header::UPGRADE,
+    header::SEC_WEBSOCKET_KEY,
+    header::SEC_WEBSOCKET_VERSION,
+    header::SEC_WEBSOCKET_EXTENSIONS,
+    header::SEC_WEBSOCKET_ACCEPT,
+];
+
+struct Upstream {
+    endpoint: RpcEndpoint,
+    http: reqwest::Client,
+}
+
+/// 엔드포인트로 가는 프록시를 띄우고 SDK가 연결할 로컬 엔드포인트를 반환합니다.
+///
+/// basic auth는 SDK가 보낸 `Authorization` 헤더를 그대로 전달합니다.
+pub fn start(endpoint: &RpcEndpoint) -> Result<RpcEndpoint> {
+    let upstream = Arc::new(Upstream {
+        endpoint: endpoint.clone(),
+        http: reqwest::Client::new(),
+    });
+    let listener = TcpListener::bind("127.0.0.1:0").context("Failed to bind RPC proxy")?;
+    listener.set_nonblocking(true)?;
+    let addr = listener.local_addr()?;
+    let app = Router::new().fallback(proxy).with_state(upstream);
+    let server = axum::Server::from_tcp(listener)?.serve(app.into_make_service());
+    tokio::spawn(async move {
+        if let Err(e) = server.await {
+            warn!(error = ?e, "RPC proxy stopped");
+        }
+    });
+    info!(
+        http_url = %endpoint.http_url,
+        proxy = %addr,
+        headers = endpoint.headers.len(),
+        "Sending RPC requests through header proxy"
+    );
+    Ok(RpcEndpoint {
+        http_url: format!("http://{}", addr),
+        ws_url: format!("ws://{}", addr),
+        ..endpoint.clone()
+    })
+}
+
+async fn proxy(
+    State(upstream): State<Arc<Upstream>>,
+    ws: Option<WebSocketUpgrade>,
+    method: Method,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let headers = upstream.headers(headers);
+    if let Some(ws) = ws {
+        return ws.on_upgrade(move |socket| async move {
+            if let Err(e) = upstream.relay(socket, headers).await {
+                warn!(ws_url = %upstream.endpoint.ws_url, error = ?e, "RPC proxy WebSocket closed");
+            }
+        });
+    }
+    match upstream.forward(method, headers, body).await {
+        Ok(response) => response,
+        Err(e) => {
+            warn!(http_url = %upstream.endpoint.http_url, error = ?e, "RPC proxy request failed");
+            (StatusCode::BAD_GATEWAY, e.to_string()).into_response()
+        }
+    }
+}
+
+impl Upstream {
+    /// 받은 요청 헤더에 설정한 헤더를 덮어씁니다.
+    fn headers(&self, mut headers: HeaderMap) -> HeaderMap {
+        for name in &HOP_BY_HOP {
+            headers.remove(name);
+        }
+        for (name, value) in &self.endpoint.headers {
+            headers.insert(name.clone(), value.clone());
+        }
+        headers
+    }
+
+    async fn forward(&self, method: Method, headers: HeaderMap, body: Bytes) -> Result<Response> {
+        let response = self
+            .http
+            .request(method, &self.endpoint.http_url)
+            .headers(headers)
+            .body(body)
+            .send()
+            .await?;
+        let status = response.status();
+        let content_type = response.headers().get(header::CONTENT_TYPE).cloned();
+        let body = response.bytes().await?;
+        let mut response = (status, body).into_response();
+        if let Some(content_type) = content_type {
+            response
+                .headers_mut()
+                .insert(header::CONTENT_TYPE, content_type);
+        }
+        Ok(response)
+    }
+
+    /// SDK와의 WebSocket과 노드로 연 WebSocket 사이에서 메시지를 주고받습니다.
+    async fn relay(&self, mut socket: WebSocket, headers: HeaderMap) -> Result<()> {
+        let mut request = self.endpoint.ws_url.as_str().into_client_request()?;
+        request.headers_mut().extend(headers);
+        let (mut node, _) = connect_async(request).await?;
+        loop {
+            tokio::select! {
+                message = socket.recv() => match message {
+                    Some(Ok(Message::Close(_))) | None => return Ok(node.close(None).await?),
+                    Some(Ok(message)) => node.send(to_node(message)).await?,
+                    Some(Err(e)) => return Err(e.into()),
+                },
+                message = node.next() => match message {
+                    Some(Ok(tungstenite::Message::Close(_))) | None => {
+                        let _ = socket.send(Message::Close(None)).await;
+                        return Ok(());
+                    }
+                    Some(Ok(message)) => {
+                        if let Some(message) = from_node(message) {
+                            socket.send(message).await?;
+                        }
+                    }
+                    Some(Err(e)) => return Err(e.into()),
+                },
+            }
+        }
+    }
+}
+
+fn to_node(message: Message) -> tungstenite::Message {
+    match message {
+        Message::Text(text) => tungstenite::Message::Text(text),
+        Message::Binary(data) => tungstenite::Message::Binary(data),
+        Message::Ping(data) => tungstenite::Message::Ping(data),
+        Message::Pong(data) => tungstenite::Message::Pong(data),
+        Message::Close(_) => tungstenite::Message::Close(None),
+    }
+}
+
+fn from_node(message: tungstenite::Message) -> Option<Message> {
+    match message {
+        tungstenite::Message::Text(text) => Some(Message::Text(text)),
+        tungstenite::Message::Binary(data) => Some(Message::Binary(data)),
+        tungstenite::Message::Ping(data) => Some(Message::Ping(data)),
+        tungstenite::Message::Pong(data) => Some(Message::Pong(data)),
+        tungstenite::Message::Close(_) => Some(Message::Close(None)),
+        tungstenite::Message::Frame(_) => None,
+    }
+}
+
+/// 설정 값 검증용. 헤더 이름과 값이 HTTP 헤더로 쓸 수 있는지 확인합니다.
+pub fn parse_header(name: &str, value: &str) -> Result<(HeaderName, HeaderValue), String> {
+    let name = HeaderName::from_bytes(name.trim().as_bytes())
+        .map_err(|e| format!("invalid header name {:?}: {}", name, e))?;
+    let value = HeaderValue::from_str(value.trim())
+        .map_err(|e| format!("invalid value for header {}: {}", name, e))?;
+    Ok((name, value))
+}
@@ -0,0 +1,49 @@
+use tokio::sync::watch;
+use tracing::{error, info};
+
+/// 종료 요청을 전달받는 수신자. 값이 `true`가 되면 종료합니다.
+pub type Shutdown = watch::Receiver<bool>;
+
+pub fn channel() -> (watch::Sender<bool>, Shutdown) {
+    watch::channel(false)
+}
+
+/// 종료 요청이 올 때까지 기다립니다. 송신자가 사라져도 종료로 간주합니다.
+pub async fn requested(shutdown: &mut Shutdown) {
+    while !*shutdown.borrow() {
+        if shutdown.changed().await.is_err() {
+            return;
+        }
+    }
+}
+
+/// SIGINT 또는 SIGTERM을 기다립니다.
+pub async fn wait_for_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            error!(error = ?e, "Failed to listen for SIGINT");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("SIGINT received"),
+        _ = terminate => info!("SIGTERM received"),
+    }
+}
@@ -1,37 +1,234 @@
-use std::time::Duration;
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc, RwLock,
+};
 
-use sui_sdk::rpc_types::{EventFilter, SuiEvent};
-use sui_sdk::{types::base_types::ObjectID, SuiClient, SuiClientBuilder};
-use tracing::info;
+use crate::{
+    config::{NetworkConfig, RpcEndpoint},
+    metrics, rpc_proxy,
+    shutdown::{self, Shutdown},
+};
+use anyhow::{Context, Result};
+use sui_sdk::{rpc_types::CheckpointId, SuiClient, SuiClientBuilder};
+use tracing::{info, warn};
 
 const SUI_MAINNET_HTTPS: &str = "https://fullnode.mainnet.sui.io:443";
 const SUI_MAINNET_WSS: &str = "wss://fullnode.mainnet.sui.io:443";
+const SUI_DEVNET_HTTPS: &str = "https://fullnode.devnet.sui.io:443";
 const SUI_DEVNET_WSS: &str = "wss://fullnode.devnet.sui.io:443";
-
+const SUI_TESTNET_HTTPS: &str = "https://fullnode.testnet.sui.io:443";
 const SUI_TESTNET_WSS: &str = "wss://testnet.suiet.app:443";
+const SUI_LOCALNET_HTTP: &str = "http://127.0.0.1:9000";
+const SUI_LOCALNET_WS: &str = "ws://127.0.0.1:9000";
 
-pub async fn get_client(build: &str) -> SuiClient {
-    let client = match build {
-        "testnet" => SuiClientBuilder::default()
-            .ws_url(SUI_TESTNET_WSS)
-            .ws_ping_interval(Duration::from_secs(1))
-            .build_testnet()
-            .await
-            .unwrap_or_else(|e| panic!("Failed to build testnet client: {}", e)),
-        "devnet" => SuiClientBuilder::default()
-            .ws_url(SUI_DEVNET_WSS)
-            .ws_ping_interval(Duration::from_secs(1))
-            .build_devnet()
-            .await
-            .unwrap_or_else(|e| panic!("Failed to build devnet client: {}", e)),
-        "mainnet" => SuiClientBuilder::default()
-            .ws_url(SUI_MAINNET_WSS)
-            .ws_ping_interval(Duration::from_secs(1))
-            .build(SUI_MAINNET_HTTPS)
+/// 네트워크 이름에 해당하는 기본 (http, ws) 엔드포인트를 반환합니다.
+pub fn preset_urls(build: &str) -> Option<(&'static str, &'static str)> {
+    match build {
+        "mainnet" => Some((SUI_MAINNET_HTTPS, SUI_MAINNET_WSS)),
+        "devnet" => Some((SUI_DEVNET_HTTPS, SUI_DEVNET_WSS)),
+        "testnet" => Some((SUI_TESTNET_HTTPS, SUI_TESTNET_WSS)),
+        "localnet" => Some((SUI_LOCALNET_HTTP, SUI_LOCALNET_WS)),
+        _ => None,
+    }
+}
+
+pub async fn get_client(endpoint: &RpcEndpoint, config: &NetworkConfig) -> Result<SuiClient> {
+    let mut builder = SuiClientBuilder::default()
+        .ws_url(&endpoint.ws_url)
+        .ws_ping_interval(config.ws_ping_interval)
+        .request_timeout(config.request_timeout);
+    // SDK가 노출하는 인증 방식은 basic auth뿐이므로 다른 헤더는 `rpc_proxy`가 붙입니다.
+    if let Some((username, password)) = &endpoint.basic_auth {
+        builder = builder.basic_auth(username, password);
+    }
+
+    let client = builder
+        .build(&endpoint.http_url)
+        .await
+        .with_context(|| format!("Failed to build {} client", endpoint.http_url))?;
+    info!(
+        http_url = %endpoint.http_url,
+        ws_url = %endpoint.ws_url,
+        "Sui client initialized"
+    );
+    Ok(client)
+}
+
+struct Node {
+    endpoint: RpcEndpoint,
+    /// 클라이언트가 연결하는 엔드포인트. 헤더가 필요하면 로컬 프록시입니다.
+    connect: RpcEndpoint,
+    client: RwLock<Option<Arc<SuiClient>>>,
+    healthy: AtomicBool,
+}
+
+/// 여러 풀노드에 대한 클라이언트 풀. 헬스체크로 상태를 갱신하고 정상 노드로 요청을 보냅니다.
+pub struct SuiClientPool {
+    config: NetworkConfig,
+    nodes: Vec<Node>,
+    current: AtomicUsize,
+}
+
+impl SuiClientPool {
+    /// 모든 엔드포인트에 연결을 시도합니다. 하나 이상 연결되어야 합니다.
+    pub async fn new(config: &NetworkConfig) -> Result<Self> {
+        let mut nodes = Vec::new();
+        for endpoint in &config.endpoints {
+            let connect = if endpoint.headers.is_empty() {
+                endpoint.clone()
+            } else {
+                rpc_proxy::start(endpoint)?
+            };
+            let client = match get_client(&connect, config).await {
+                Ok(client) => Some(Arc::new(client)),
+                Err(e) => {
+                    warn!(error = ?e, "Failed to connect to Sui RPC");
+                    None
+                }
+            };
+            nodes.push(Node {
+                endpoint: endpoint.clone(),
+                connect,
+                healthy: AtomicBool::new(client.is_some()),
+                client: RwLock::new(client),
+            });
+        }
+
+        let current = nodes
+            .iter()
+            .position(|node| node.healthy.load(Ordering::Relaxed))
+            .context("No Sui RPC endpoint is reachable")?;
+
+        Ok(SuiClientPool {
+            config: config.clone(),
+            nodes,
+            current: AtomicUsize::new(current),
+        })
+    }
+
+    /// 현재 사용 중인 노드의 클라이언트를 반환합니다.
+    pub fn client(&self) -> Arc<SuiClient> {
+        let current = self.current.load(Ordering::Relaxed);
+        if !self.nodes[current].healthy.load(Ordering::Relaxed) {
+            self.switch_from(current);
+        }
+        self.connected_client()
+    }
+
+    /// 현재 노드를 비정상으로 표시하고 다음 정상 노드로 전환합니다.
+    pub fn failover(&self) {
+        let current = self.current.load(Ordering::Relaxed);
+        self.nodes[current].healthy.store(false, Ordering::Relaxed);
+        metrics::RPC_FAILOVERS.inc();
+        self.switch_from(current);
+    }
+
+    /// 정상으로 확인된 노드가 하나라도 있는지 반환합니다.
+    pub fn is_healthy(&self) -> bool {
+        self.nodes
+            .iter()
+            .any(|node| node.healthy.load(Ordering::Relaxed))
+    }
+
+    pub fn current_endpoint(&self) -> &RpcEndpoint {
+        &self.nodes[self.current.load(Ordering::Relaxed)].endpoint
+    }
+
+    /// 종료 요청 전까지 주기적으로 모든 노드의 상태를 확인합니다.
+    pub async fn run_health_checks(self: Arc<Self>, mut shutdown: Shutdown) -> Result<()> {
+        let mut interval = tokio::time::interval(self.config.health_check_interval);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => self.health_check().await,
+                _ = shutdown::requested(&mut shutdown) => return Ok(()),
+            }
+        }
+    }
+
+    pub async fn health_check(&self) {
+        for node in &self.nodes {
+            let healthy = self.check_node(node).await;
+            if healthy != node.healthy.swap(healthy, Ordering::Relaxed) {
+                info!(
+                    endpoint = %node.endpoint.http_url,
+                    healthy,
+                    "Sui RPC health changed"
+                );
+            }
+        }
+    }
+
+    async fn check_node(&self, node: &Node) -> bool {
+        let client = node.client.read().unwrap().clone();
+        let client = match client {
+            Some(client) => client,
+            // 시작 시 연결하지 못한 노드는 다시 연결을 시도합니다.
+            None => match get_client(&node.connect, &self.config).await {
+                Ok(client) => {
+                    let client = Arc::new(client);
+                    *node.client.write().unwrap() = Some(client.clone());
+                    client
+                }
+                Err(_) => return false,
+            },
+        };
+
+        let checkpoint = tokio::time::timeout(self.config.request_timeout, async {
+            let sequence_number = metrics::rpc(
+                "get_latest_checkpoint_sequence_number",
+                client.read_api().get_latest_checkpoint_sequence_number(),
+            )
+            .await?;
+            metrics::rpc(
+                "get_checkpoint",
+                client
+                    .read_api()
+                    .get_checkpoint(CheckpointId::SequenceNumber(sequence_number)),
+            )
             .await
-            .unwrap_or_else(|e| panic!("Failed to build mainnet client: {}", e)),
-        _ => panic!("Invalid build type: {}", build),
-    };
-    info!("Sui Client initialized!");
-    client
+        })
+        .await;
+        match checkpoint {
+            Ok(Ok(checkpoint)) => {
+                metrics::record_chain_head(checkpoint.timestamp_ms);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn switch_from(&self, from: usize) {
+        let len = self.nodes.len();
+        let next = (1..len)
+            .map(|offset| (from + offset) % len)
+            .find(|&index| self.nodes[index].healthy.load(Ordering::Relaxed))
+            // 모두 비정상이면 연결된 다음 노드를 순서대로 시도합니다.
+            .or_else(|| {
+                (1..=len)
+                    .map(|offset| (from + offset) % len)
+                    .find(|&index| self.nodes[index].client.read().unwrap().is_some())
+            })
+            .unwrap_or(from);
+
+        if self
+            .current
+            .compare_exchange(from, next, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+            && next != from
+        {
+            warn!(
+                "Sui RPC failover: {} -> {}",
+                self.nodes[from].endpoint.http_url, self.nodes[next].endpoint.http_url
+            );
+        }
+    }
+
+    fn connected_client(&self) -> Arc<SuiClient> {
+        let current = self.current.load(Ordering::Relaxed);
+        let len = self.nodes.len();
+        (0..len)
+            .map(|offset| (current + offset) % len)
+            .find_map(|index| self.nodes[index].client.read().unwrap().clone())
+            .expect("at least one Sui client is connected")
+    }
 }
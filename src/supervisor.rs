@@ -0,0 +1,142 @@
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    sync::{Arc, RwLock},
+    time::Instant,
+};
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use tracing::{error, info, warn};
+
+use crate::{
+    config::SupervisorConfig,
+    shutdown::{self, Shutdown},
+};
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(tag = "state", rename_all = "camelCase")]
+pub enum ComponentState {
+    Running,
+    Restarting {
+        attempt: u32,
+        #[serde(rename = "lastError")]
+        last_error: String,
+    },
+    Failed {
+        #[serde(rename = "lastError")]
+        last_error: String,
+    },
+    Stopped,
+}
+
+/// 컴포넌트 이름별 현재 상태. API와 로그에서 조회합니다.
+#[derive(Debug, Clone, Default)]
+pub struct ComponentStates(Arc<RwLock<BTreeMap<String, ComponentState>>>);
+
+impl ComponentStates {
+    pub fn snapshot(&self) -> BTreeMap<String, ComponentState> {
+        self.0.read().unwrap().clone()
+    }
+
+    fn set(&self, name: &str, state: ComponentState) {
+        info!(component = name, state = ?state, "Component state changed");
+        self.0.write().unwrap().insert(name.to_string(), state);
+    }
+}
+
+/// 파이프라인 태스크를 실행하고 실패하면 backoff 후 다시 시작합니다.
+#[derive(Debug, Clone)]
+pub struct Supervisor {
+    config: SupervisorConfig,
+    states: ComponentStates,
+}
+
+impl Supervisor {
+    pub fn new(config: SupervisorConfig) -> Self {
+        Supervisor {
+            config,
+            states: ComponentStates::default(),
+        }
+    }
+
+    pub fn states(&self) -> ComponentStates {
+        self.states.clone()
+    }
+
+    /// `start`로 만든 태스크를 감시합니다.
+    ///
+    /// 종료 요청 없이 끝나거나 오류/패닉이 나면 재시작하고, 연속 실패가 `max_restarts`를 넘으면
+    /// 오류를 반환해 프로세스 종료로 이어지게 합니다.
+    pub async fn supervise<F, Fut>(
+        self,
+        name: impl Into<String>,
+        mut shutdown: Shutdown,
+        start: F,
+    ) -> Result<()>
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let name = name.into();
+        let name = name.as_str();
+        let mut failures = 0;
+        let mut backoff = self.config.initial_backoff;
+
+        loop {
+            self.states.set(name, ComponentState::Running);
+            let started = Instant::now();
+            let result = match tokio::spawn(start()).await {
+                Ok(result) => result,
+                Err(e) if e.is_panic() => Err(anyhow!("panicked: {}", e)),
+                Err(e) => Err(anyhow!("cancelled: {}", e)),
+            };
+
+            if *shutdown.borrow() {
+                if let Err(e) = result {
+                    warn!(component = name, error = ?e, "Component stopped with error");
+                }
+                self.states.set(name, ComponentState::Stopped);
+                return Ok(());
+            }
+
+            let last_error = match result {
+                Ok(()) => "exited unexpectedly".to_string(),
+                Err(e) => format!("{:?}", e),
+            };
+            error!(component = name, error = %last_error, "Component failed");
+
+            // 오래 정상 동작했다면 이전 실패는 일시적인 것으로 보고 초기화합니다.
+            if started.elapsed() >= self.config.stable_after {
+                failures = 0;
+                backoff = self.config.initial_backoff;
+            }
+            failures += 1;
+
+            if failures > self.config.max_restarts {
+                self.states.set(name, ComponentState::Failed { last_error });
+                return Err(anyhow!(
+                    "Component {} failed {} times in a row, giving up",
+                    name,
+                    failures
+                ));
+            }
+
+            self.states.set(
+                name,
+                ComponentState::Restarting {
+                    attempt: failures,
+                    last_error,
+                },
+            );
+            tokio::select! {
+                _ = tokio::time::sleep(backoff) => {}
+                _ = shutdown::requested(&mut shutdown) => {
+                    self.states.set(name, ComponentState::Stopped);
+                    return Ok(());
+                }
+            }
+            backoff = (backoff * 2).min(self.config.max_backoff);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{runtime, trace, Resource};
+use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+use crate::config::{LogConfig, LogFormat, OtlpConfig};
+
+/// 설정한 레벨과 형식으로 전역 tracing subscriber를 설치합니다.
+///
+/// `log` 크레이트로 남긴 의존성 로그도 같은 subscriber로 전달됩니다.
+/// OTLP 수집기가 설정되어 있으면 span을 trace로 함께 내보냅니다.
+pub fn init(config: &LogConfig, otlp: Option<&OtlpConfig>) -> anyhow::Result<()> {
+    let otel = otlp
+        .map(otlp_tracer)
+        .transpose()?
+        .map(|tracer| tracing_opentelemetry::layer().with_tracer(tracer));
+    let registry = tracing_subscriber::registry()
+        .with(EnvFilter::try_new(&config.level)?)
+        .with(otel);
+    match config.format {
+        LogFormat::Text => registry.with(fmt::layer()).try_init()?,
+        // span 필드(tx_digest, coin_type 등)가 각 로그 줄에 함께 기록됩니다.
+        LogFormat::Json => registry
+            .with(
+                fmt::layer()
+                    .json()
+                    .with_current_span(true)
+                    .with_span_list(true),
+            )
+            .try_init()?,
+    }
+    Ok(())
+}
+
+/// 아직 내보내지 않은 span을 전송하고 exporter를 닫습니다.
+pub fn shutdown() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+fn otlp_tracer(config: &OtlpConfig) -> anyhow::Result<trace::Tracer> {
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(&config.endpoint),
+        )
+        .with_trace_config(
+            trace::config().with_resource(Resource::new(vec![KeyValue::new(
+                "service.name",
+                config.service_name.clone(),
+            )])),
+        )
+        .install_batch(runtime::Tokio)?;
+    Ok(tracer)
+}
@@ -0,0 +1,129 @@
+//! 로컬 포트에 띄운 API 라우터로 거래 페이지 나누기와 `api` 모드의 실시간 스트림 거절을 확인합니다.
+
+use std::{net::TcpListener, sync::Arc};
+
+use gmi_server::{
+    api::{self, graphql, AppState},
+    db::{
+        model::{MarketUpdate, SwapEvent},
+        Database,
+    },
+    supervisor::ComponentStates,
+};
+use reqwest::StatusCode;
+use serde_json::Value;
+use sui_sdk::types::base_types::{ObjectID, SuiAddress};
+use tokio::sync::broadcast::Sender;
+
+const COIN_TYPE: &str = "0x2::meme::MEME";
+
+/// 라우터를 띄우고 기본 URL을 반환합니다.
+async fn serve(db: Arc<Database>, updates: Option<Sender<MarketUpdate>>) -> String {
+    let state = AppState {
+        schema: graphql::schema(db.clone()),
+        db,
+        updates,
+        components: ComponentStates::default(),
+    };
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = axum::Server::from_tcp(listener)
+        .unwrap()
+        .serve(api::router(state, false).into_make_service());
+    tokio::spawn(server);
+    format!("http://{}", addr)
+}
+
+fn swap(index: u64) -> SwapEvent {
+    SwapEvent {
+        account: SuiAddress::ZERO,
+        pool_id: ObjectID::ZERO,
+        meme_in_amount: 0,
+        meme_out_amount: 100,
+        sui_in_amount: 10 + index,
+        sui_out_amount: 0,
+        reserve_meme: 1_000,
+        reserve_sui: 1_000,
+        timestamp: Some(1_718_000_000_000 + index),
+        coin_type: Some(COIN_TYPE.to_string()),
+        account_meme_balance: Some(100),
+        digest: Some(format!("tx{}", index)),
+        event_seq: Some(0),
+        current_price: None,
+        package_version: Some(1),
+        venue: None,
+    }
+}
+
+async fn get_json(url: &str) -> Value {
+    let response = reqwest::get(url).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK, "{}", url);
+    response.json().await.unwrap()
+}
+
+fn hashes(page: &Value) -> Vec<&str> {
+    page["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|trade| trade["transactionHash"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn pages_trades_newest_first() {
+    let db = Arc::new(Database::in_memory().await.unwrap());
+    for index in 0..5 {
+        db.save_trade_data(swap(index)).await.unwrap();
+    }
+    let base = serve(db, None).await;
+
+    let page = get_json(&format!("{}/trades/{}?offset=1&limit=2", base, COIN_TYPE)).await;
+    assert_eq!(page["total"], 5);
+    assert_eq!(page["offset"], 1);
+    assert_eq!(page["limit"], 2);
+    assert_eq!(hashes(&page), ["tx3", "tx2"]);
+
+    let page = get_json(&format!("{}/trades/{}?offset=4", base, COIN_TYPE)).await;
+    assert_eq!(hashes(&page), ["tx0"]);
+
+    // 한도를 넘는 limit은 줄여서 응답합니다.
+    let page = get_json(&format!("{}/trades/{}?limit=1000", base, COIN_TYPE)).await;
+    assert_eq!(page["limit"], 200);
+    assert_eq!(hashes(&page).len(), 5);
+
+    let page = get_json(&format!("{}/trades/0x3::other::OTHER", base)).await;
+    assert_eq!(page["total"], 0);
+    assert!(hashes(&page).is_empty());
+}
+
+#[tokio::test]
+async fn live_streams_are_unavailable_without_an_observer() {
+    let db = Arc::new(Database::in_memory().await.unwrap());
+    let base = serve(db, None).await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/launches", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let body: Value = response.json().await.unwrap();
+    assert!(body["error"].as_str().unwrap().contains("mode `all`"));
+
+    let response = client
+        .get(format!("{}/ws", base))
+        .header("connection", "upgrade")
+        .header("upgrade", "websocket")
+        .header("sec-websocket-version", "13")
+        .header("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ==")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    // 다른 요청은 그대로 처리합니다.
+    let response = client.get(format!("{}/tokens", base)).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
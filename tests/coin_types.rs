@@ -0,0 +1,85 @@
+//! 풀 id별 코인 타입 캐시가 저장된 풀과 한 번 조회한 풀을 다시 조회하지 않는지 확인합니다.
+
+mod common;
+
+use std::time::Duration;
+
+use common::mock_rpc::{MockNode, MockState};
+use gmi_server::{
+    config::{NetworkConfig, RpcEndpoint},
+    db::{model::CreatePoolEvent, Database},
+    observe::CoinTypeCache,
+    sui::SuiClientPool,
+};
+use sui_sdk::types::base_types::{ObjectID, SuiAddress};
+
+const AMM_PACKAGE: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+const SAVED_COIN: &str = "0x2::meme::SAVED";
+const NEW_COIN: &str = "0x2::meme::NEW";
+
+fn network(node: &MockNode) -> NetworkConfig {
+    NetworkConfig {
+        build: "custom".to_string(),
+        endpoints: vec![RpcEndpoint {
+            http_url: node.http_url.clone(),
+            ws_url: node.ws_url.clone(),
+            basic_auth: None,
+            headers: vec![],
+        }],
+        ws_ping_interval: Duration::from_secs(30),
+        request_timeout: Duration::from_secs(5),
+        health_check_interval: Duration::from_secs(60),
+    }
+}
+
+fn create_pool(pool_id: ObjectID, coin_type: &str) -> CreatePoolEvent {
+    CreatePoolEvent {
+        coin_type: Some(coin_type.to_string()),
+        metadata_id: ObjectID::ZERO,
+        pool_id,
+        reserve_meme: 1_000,
+        reserve_sui: 10,
+        account: SuiAddress::ZERO,
+        treasury_id: ObjectID::ZERO,
+        timestamp: Some(100),
+        digest: Some("create".to_string()),
+        package_version: Some(1),
+    }
+}
+
+#[tokio::test]
+async fn resolves_each_pool_once() {
+    let saved_pool = ObjectID::from_single_byte(1);
+    let new_pool = ObjectID::from_single_byte(2);
+    let state = MockState::default();
+    state.objects.lock().unwrap().insert(
+        new_pool,
+        format!("{}::amm::Pool<{}>", AMM_PACKAGE, NEW_COIN),
+    );
+    let node = MockNode::start(state);
+    let pool = SuiClientPool::new(&network(&node)).await.unwrap();
+    let db = Database::in_memory().await.unwrap();
+    db.save_pool(create_pool(saved_pool, SAVED_COIN))
+        .await
+        .unwrap();
+
+    let coin_types = CoinTypeCache::load(&db).await.unwrap();
+    // 저장된 풀은 풀 객체를 조회하지 않습니다.
+    let coin_type = coin_types.get(pool.client(), saved_pool).await.unwrap();
+    assert_eq!(coin_type, SAVED_COIN);
+    assert!(node.state.requests("sui_getObject").is_empty());
+
+    for _ in 0..2 {
+        let coin_type = coin_types.get(pool.client(), new_pool).await.unwrap();
+        assert_eq!(coin_type, NEW_COIN);
+    }
+    assert_eq!(node.state.requests("sui_getObject").len(), 1);
+
+    // 없는 풀은 캐시하지 않고 다시 시도해도 같은 결과인 오류로 돌려줍니다.
+    let missing = ObjectID::from_single_byte(3);
+    let error = coin_types.get(pool.client(), missing).await.unwrap_err();
+    assert_eq!(error.kind(), "missing");
+    assert!(!error.is_transient());
+    coin_types.get(pool.client(), missing).await.unwrap_err();
+    assert_eq!(node.state.requests("sui_getObject").len(), 3);
+}
@@ -0,0 +1,268 @@
+//! 테스트용 Sui JSON-RPC 노드
+//!
+//! HTTP(`POST /`)와 WebSocket(`GET /`)으로 구독과 조회에 필요한 메서드만 흉내 냅니다.
+//! 풀노드처럼 `suix_queryEvents`는 `Package` 필터를 거절합니다.
+
+use std::{
+    collections::HashMap,
+    net::TcpListener,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    http::HeaderMap,
+    response::Response,
+    routing::get,
+    Json, Router,
+};
+use serde_json::{json, Value};
+use sui_sdk::{
+    rpc_types::{EventFilter, SuiEvent},
+    types::{base_types::ObjectID, digests::ObjectDigest, event::EventID},
+};
+
+const SUBSCRIPTION_ID: u64 = 7;
+
+#[derive(Default)]
+pub struct MockState {
+    /// `suix_queryEvents`가 조회하는 이벤트 (오래된 순)
+    pub history: Mutex<Vec<SuiEvent>>,
+    /// 구독하면 보내는 이벤트
+    pub live: Mutex<Vec<SuiEvent>>,
+    /// `sui_getObject`가 돌려주는 객체 id별 타입
+    pub objects: Mutex<HashMap<ObjectID, String>>,
+    /// 패키지의 모듈 이름과 모듈이 자기 주소로 쓰는 (원래) 패키지 주소
+    pub modules: Mutex<Vec<(String, String)>>,
+    /// 구독 요청을 거절할지
+    pub fail_subscribe: AtomicBool,
+    /// 받은 요청의 (메서드, 인자)
+    pub requests: Mutex<Vec<(String, Value)>>,
+    /// 받은 HTTP 요청과 WebSocket 핸드셰이크의 헤더
+    pub headers: Mutex<Vec<HeaderMap>>,
+}
+
+impl MockState {
+    pub fn requests(&self, method: &str) -> Vec<Value> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(name, _)| name == method)
+            .map(|(_, params)| params.clone())
+            .collect()
+    }
+
+    fn handle(&self, request: &Value) -> Value {
+        let id = request["id"].clone();
+        let method = request["method"].as_str().unwrap_or_default();
+        let params = request["params"].clone();
+        self.requests
+            .lock()
+            .unwrap()
+            .push((method.to_string(), params.clone()));
+        match method {
+            "rpc.discover" => success(id, discover()),
+            "suix_queryEvents" => match self.query_events(&params) {
+                Ok(page) => success(id, page),
+                Err(message) => failure(id, -32602, &message),
+            },
+            "sui_getNormalizedMoveModulesByPackage" => success(id, self.normalized_modules()),
+            "sui_getObject" => match serde_json::from_value(params[0].clone()) {
+                Ok(object_id) => success(id, self.object(object_id)),
+                Err(e) => failure(id, -32602, &e.to_string()),
+            },
+            _ => failure(id, -32601, "Method not found"),
+        }
+    }
+
+    fn query_events(&self, params: &Value) -> Result<Value, String> {
+        let filter: EventFilter =
+            serde_json::from_value(params[0].clone()).map_err(|e| e.to_string())?;
+        let cursor: Option<EventID> =
+            serde_json::from_value(params[1].clone()).map_err(|e| e.to_string())?;
+        let matches = |event: &SuiEvent| match &filter {
+            EventFilter::MoveEventModule { package, module } => {
+                ObjectID::from(event.type_.address) == *package && &event.type_.module == module
+            }
+            EventFilter::MoveEventType(event_type) => &event.type_ == event_type,
+            _ => false,
+        };
+        if !matches!(
+            filter,
+            EventFilter::MoveEventModule { .. } | EventFilter::MoveEventType(_)
+        ) {
+            return Err("This query type is not supported by the full node.".to_string());
+        }
+
+        let history = self.history.lock().unwrap();
+        let start = match cursor {
+            Some(cursor) => history
+                .iter()
+                .position(|event| event.id == cursor)
+                .map_or(0, |index| index + 1),
+            None => 0,
+        };
+        let mut data: Vec<&SuiEvent> = history[start..].iter().filter(|e| matches(e)).collect();
+        // 내림차순 조회는 cursor 없이 최신 이벤트를 볼 때만 씁니다.
+        if params[3].as_bool().unwrap_or(false) {
+            data.reverse();
+        }
+        if let Some(limit) = params[2].as_u64() {
+            data.truncate(limit as usize);
+        }
+        let next_cursor = data.last().map(|event| event.id.clone());
+        Ok(json!({ "data": data, "nextCursor": next_cursor, "hasNextPage": false }))
+    }
+
+    fn object(&self, object_id: ObjectID) -> Value {
+        match self.objects.lock().unwrap().get(&object_id) {
+            Some(object_type) => json!({
+                "data": {
+                    "objectId": object_id,
+                    "version": "1",
+                    "digest": ObjectDigest::new([1; 32]),
+                    "type": object_type
+                }
+            }),
+            None => json!({ "error": { "code": "notExists", "object_id": object_id } }),
+        }
+    }
+
+    fn normalized_modules(&self) -> Value {
+        let modules = self.modules.lock().unwrap();
+        let modules: serde_json::Map<String, Value> = modules
+            .iter()
+            .map(|(name, address)| {
+                let module = json!({
+                    "fileFormatVersion": 6,
+                    "address": address,
+                    "name": name,
+                    "friends": [],
+                    "structs": {},
+                    "exposedFunctions": {}
+                });
+                (name.clone(), module)
+            })
+            .collect();
+        Value::Object(modules)
+    }
+}
+
+pub struct MockNode {
+    pub http_url: String,
+    pub ws_url: String,
+    pub state: Arc<MockState>,
+}
+
+impl MockNode {
+    pub fn start(state: MockState) -> Self {
+        let state = Arc::new(state);
+        let app = Router::new()
+            .route("/", get(ws_handler).post(http_handler))
+            .with_state(state.clone());
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service()),
+        );
+        MockNode {
+            http_url: format!("http://{}", addr),
+            ws_url: format!("ws://{}", addr),
+            state,
+        }
+    }
+}
+
+async fn http_handler(
+    State(state): State<Arc<MockState>>,
+    headers: HeaderMap,
+    body: String,
+) -> Json<Value> {
+    state.headers.lock().unwrap().push(headers);
+    let request: Value = serde_json::from_str(&body).unwrap();
+    Json(state.handle(&request))
+}
+
+async fn ws_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<Arc<MockState>>,
+    headers: HeaderMap,
+) -> Response {
+    state.headers.lock().unwrap().push(headers);
+    ws.on_upgrade(move |socket| ws_session(socket, state))
+}
+
+async fn ws_session(mut socket: WebSocket, state: Arc<MockState>) {
+    while let Some(Ok(message)) = socket.recv().await {
+        let Message::Text(text) = message else {
+            continue;
+        };
+        let request: Value = serde_json::from_str(&text).unwrap();
+        let id = request["id"].clone();
+        let mut replies = Vec::new();
+        match request["method"].as_str().unwrap_or_default() {
+            "suix_subscribeEvent" => {
+                state
+                    .requests
+                    .lock()
+                    .unwrap()
+                    .push(("suix_subscribeEvent".to_string(), request["params"].clone()));
+                if state.fail_subscribe.load(Ordering::Relaxed) {
+                    replies.push(failure(id, -32000, "Subscription rejected"));
+                } else {
+                    replies.push(success(id, json!(SUBSCRIPTION_ID)));
+                    for event in state.live.lock().unwrap().iter() {
+                        replies.push(json!({
+                            "jsonrpc": "2.0",
+                            "method": "suix_subscribeEvent",
+                            "params": { "subscription": SUBSCRIPTION_ID, "result": event }
+                        }));
+                    }
+                }
+            }
+            "suix_unsubscribeEvent" => replies.push(success(id, json!(true))),
+            _ => replies.push(state.handle(&request)),
+        }
+        for reply in replies {
+            if socket.send(Message::Text(reply.to_string())).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+fn discover() -> Value {
+    let methods: Vec<Value> = [
+        "rpc.discover",
+        "suix_queryEvents",
+        "suix_subscribeEvent",
+        "sui_getNormalizedMoveModulesByPackage",
+        "sui_getObject",
+    ]
+    .iter()
+    .map(|name| json!({ "name": name, "params": [] }))
+    .collect();
+    json!({
+        "openrpc": "1.2.6",
+        "info": { "title": "Sui JSON-RPC", "version": "1.26.0" },
+        "methods": methods
+    })
+}
+
+fn success(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn failure(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
@@ -0,0 +1,48 @@
+//! 통합 테스트에서 함께 쓰는 도구
+
+#![allow(dead_code)]
+
+pub mod mock_rpc;
+
+use serde_json::Value;
+use sui_sdk::rpc_types::SuiEvent;
+
+/// `tests/fixtures` 아래의 이벤트(JSON-RPC 응답 형식)를 읽습니다.
+pub fn fixture(name: &str) -> SuiEvent {
+    let path = format!(
+        "{}/tests/fixtures/{}.json",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    );
+    let json = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    serde_json::from_str(&json).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+/// `parsedJson`의 필드. 노드가 필드 이름으로 풀어 준 값이라 BCS 필드 순서를 검증하는 기준이 됩니다.
+pub fn json_field<'a>(event: &'a SuiEvent, path: &str) -> &'a Value {
+    event
+        .parsed_json
+        .pointer(path)
+        .unwrap_or_else(|| panic!("{} missing in parsedJson", path))
+}
+
+/// Move의 u64/u128은 JSON에서 문자열로 옵니다.
+pub fn json_u64(event: &SuiEvent, path: &str) -> u64 {
+    let value = json_field(event, path);
+    value
+        .as_str()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| panic!("{} is not a u64 string: {}", path, value))
+}
+
+pub fn json_bool(event: &SuiEvent, path: &str) -> bool {
+    json_field(event, path)
+        .as_bool()
+        .unwrap_or_else(|| panic!("{} is not a bool", path))
+}
+
+pub fn json_str<'a>(event: &'a SuiEvent, path: &str) -> &'a str {
+    json_field(event, path)
+        .as_str()
+        .unwrap_or_else(|| panic!("{} is not a string", path))
+}
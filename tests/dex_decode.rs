@@ -0,0 +1,147 @@
+//! 외부 DEX 스왑 이벤트의 BCS 레이아웃과 정규화 검증
+//!
+//! `event_decode.rs`와 같이 `parsedJson`과 디코딩 결과를 비교합니다.
+
+mod common;
+
+use common::{fixture, json_bool, json_field, json_str, json_u64};
+use gmi_server::{
+    dex::{CetusSwapEvent, DeepBookOrderFilled, DexSwap, TurbosSwapEvent, Venue},
+    event::decode,
+};
+use sui_sdk::{rpc_types::SuiEvent, types::Identifier};
+
+fn json_u128(event: &SuiEvent, path: &str) -> u128 {
+    json_str(event, path).parse().unwrap()
+}
+
+fn json_u32(event: &SuiEvent, path: &str) -> u32 {
+    json_field(event, path).as_u64().unwrap() as u32
+}
+
+#[test]
+fn decodes_cetus_swap_event() {
+    let event = fixture("dex/cetus_swap_event");
+    let swap = decode::<CetusSwapEvent>(&event).unwrap();
+    assert_eq!(swap.atob, json_bool(&event, "/atob"));
+    assert_eq!(swap.pool.to_string(), json_str(&event, "/pool"));
+    assert_eq!(swap.partner.to_string(), json_str(&event, "/partner"));
+    assert_eq!(swap.amount_in, json_u64(&event, "/amount_in"));
+    assert_eq!(swap.amount_out, json_u64(&event, "/amount_out"));
+    assert_eq!(swap.ref_amount, json_u64(&event, "/ref_amount"));
+    assert_eq!(swap.fee_amount, json_u64(&event, "/fee_amount"));
+    assert_eq!(swap.vault_a_amount, json_u64(&event, "/vault_a_amount"));
+    assert_eq!(swap.vault_b_amount, json_u64(&event, "/vault_b_amount"));
+    assert_eq!(
+        swap.before_sqrt_price,
+        json_u128(&event, "/before_sqrt_price")
+    );
+    assert_eq!(
+        swap.after_sqrt_price,
+        json_u128(&event, "/after_sqrt_price")
+    );
+    assert_eq!(swap.steps, json_u64(&event, "/steps"));
+
+    let swap = DexSwap::parse(Venue::Cetus, &event).unwrap();
+    assert_eq!(swap.account, event.sender);
+    assert!(swap.a_to_b);
+    assert_eq!(swap.amount_in, json_u64(&event, "/amount_in"));
+    assert_eq!(swap.amount_out, json_u64(&event, "/amount_out"));
+}
+
+#[test]
+fn decodes_turbos_swap_event() {
+    let event = fixture("dex/turbos_swap_event");
+    let swap = decode::<TurbosSwapEvent>(&event).unwrap();
+    assert_eq!(swap.pool.to_string(), json_str(&event, "/pool"));
+    assert_eq!(swap.recipient.to_string(), json_str(&event, "/recipient"));
+    assert_eq!(swap.amount_a, json_u64(&event, "/amount_a"));
+    assert_eq!(swap.amount_b, json_u64(&event, "/amount_b"));
+    assert_eq!(swap.liquidity, json_u128(&event, "/liquidity"));
+    assert_eq!(
+        swap.tick_current_index.bits,
+        json_u32(&event, "/tick_current_index/bits")
+    );
+    assert_eq!(
+        swap.tick_pre_index.bits,
+        json_u32(&event, "/tick_pre_index/bits")
+    );
+    assert_eq!(swap.sqrt_price, json_u128(&event, "/sqrt_price"));
+    assert_eq!(swap.protocol_fee, json_u64(&event, "/protocol_fee"));
+    assert_eq!(swap.fee_amount, json_u64(&event, "/fee_amount"));
+    assert_eq!(swap.a_to_b, json_bool(&event, "/a_to_b"));
+    assert_eq!(swap.is_exact_in, json_bool(&event, "/is_exact_in"));
+
+    // b를 내고 a를 받았습니다.
+    let swap = DexSwap::parse(Venue::Turbos, &event).unwrap();
+    assert!(!swap.a_to_b);
+    assert_eq!(swap.amount_in, json_u64(&event, "/amount_b"));
+    assert_eq!(swap.amount_out, json_u64(&event, "/amount_a"));
+}
+
+#[test]
+fn decodes_deepbook_order_filled() {
+    let event = fixture("dex/deepbook_order_filled");
+    let fill = decode::<DeepBookOrderFilled>(&event).unwrap();
+    assert_eq!(fill.pool_id.to_string(), json_str(&event, "/pool_id"));
+    assert_eq!(fill.maker_order_id, json_u128(&event, "/maker_order_id"));
+    assert_eq!(fill.taker_order_id, json_u128(&event, "/taker_order_id"));
+    assert_eq!(
+        fill.maker_client_order_id,
+        json_u64(&event, "/maker_client_order_id")
+    );
+    assert_eq!(
+        fill.taker_client_order_id,
+        json_u64(&event, "/taker_client_order_id")
+    );
+    assert_eq!(fill.price, json_u64(&event, "/price"));
+    assert_eq!(fill.taker_is_bid, json_bool(&event, "/taker_is_bid"));
+    assert_eq!(fill.taker_fee, json_u64(&event, "/taker_fee"));
+    assert_eq!(
+        fill.taker_fee_is_deep,
+        json_bool(&event, "/taker_fee_is_deep")
+    );
+    assert_eq!(fill.maker_fee, json_u64(&event, "/maker_fee"));
+    assert_eq!(
+        fill.maker_fee_is_deep,
+        json_bool(&event, "/maker_fee_is_deep")
+    );
+    assert_eq!(fill.base_quantity, json_u64(&event, "/base_quantity"));
+    assert_eq!(fill.quote_quantity, json_u64(&event, "/quote_quantity"));
+    assert_eq!(
+        fill.maker_balance_manager_id.to_string(),
+        json_str(&event, "/maker_balance_manager_id")
+    );
+    assert_eq!(
+        fill.taker_balance_manager_id.to_string(),
+        json_str(&event, "/taker_balance_manager_id")
+    );
+    assert_eq!(fill.timestamp, json_u64(&event, "/timestamp"));
+
+    // taker가 매도했으므로 base를 내고 quote(SUI)를 받았습니다.
+    let swap = DexSwap::parse(Venue::DeepBook, &event).unwrap();
+    assert_eq!(swap.account, event.sender);
+    assert!(swap.a_to_b);
+    assert_eq!(swap.amount_in, 4_000_000_000);
+    assert_eq!(swap.amount_out, 24_000_000);
+}
+
+#[test]
+fn deepbook_taker_bid_pays_quote() {
+    let mut event = fixture("dex/deepbook_order_filled");
+    event.parsed_json["taker_is_bid"] = true.into();
+    // BCS의 taker_is_bid는 32 + 16 + 16 + 8 * 3 바이트 뒤에 있습니다.
+    event.bcs[88] = 1;
+
+    let swap = DexSwap::parse(Venue::DeepBook, &event).unwrap();
+    assert!(!swap.a_to_b);
+    assert_eq!(swap.amount_in, 24_000_000);
+    assert_eq!(swap.amount_out, 4_000_000_000);
+}
+
+#[test]
+fn rejects_deprecated_clob_v2_order_filled() {
+    let mut event = fixture("dex/deepbook_order_filled");
+    event.type_.module = Identifier::new("clob_v2").unwrap();
+    assert!(DexSwap::parse(Venue::DeepBook, &event).is_err());
+}
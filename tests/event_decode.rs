@@ -0,0 +1,196 @@
+//! AMM 이벤트의 BCS 레이아웃 검증
+//!
+//! BCS는 필드 이름 없이 순서대로 인코딩되므로, 같은 이벤트의 `parsedJson`(필드 이름별 값)과
+//! 디코딩 결과를 비교해 구조체 필드 순서가 Move 정의와 같은지 확인합니다.
+//! 레이아웃이 어긋났을 때 `decode`가 `parsedJson`을 쓰는지와 다른 타입을 거절하는지도 확인합니다.
+
+mod common;
+
+use common::{fixture, json_str, json_u64};
+use gmi_server::{
+    event::{
+        decode, MoveAddLiquidityEvent, MoveCreatePoolEvent, MoveGraduateEvent, MoveLiquidityEvent,
+        MoveMigratePoolEvent, MoveRemoveLiquidityEvent, MoveSwapEvent,
+    },
+    metrics,
+};
+use serde_json::json;
+use sui_sdk::{
+    rpc_types::SuiEvent,
+    types::{
+        base_types::{ObjectID, SuiAddress},
+        Identifier,
+    },
+};
+
+const PACKAGE: &str = "0x9b34c6c4feba1aaf9ce404f72495ab0d3d00c6a53c68ae16be3644c3af3f4ac9";
+
+/// 필드 값이 `swap`인 AMM `SwapEvent`. BCS는 `bcs`로 따로 넣습니다.
+fn swap_event(swap: &MoveSwapEvent, bcs: Vec<u8>) -> SuiEvent {
+    let mut event: SuiEvent = serde_json::from_value(json!({
+        "id": {
+            "txDigest": "8hjQRfY26i5n89siEcWZw1GMWFQo3Nw2DRaydAfT3SuM",
+            "eventSeq": "0"
+        },
+        "packageId": PACKAGE,
+        "transactionModule": "amm",
+        "sender": swap.account.to_string(),
+        "type": format!("{}::amm::SwapEvent", PACKAGE),
+        "parsedJson": {
+            "account": swap.account.to_string(),
+            "pool_id": swap.pool_id.to_string(),
+            "meme_in_amount": swap.meme_in_amount.to_string(),
+            "meme_out_amount": swap.meme_out_amount.to_string(),
+            "sui_in_amount": swap.sui_in_amount.to_string(),
+            "sui_out_amount": swap.sui_out_amount.to_string(),
+            "reserve_meme": swap.reserve_meme.to_string(),
+            "reserve_sui": swap.reserve_sui.to_string()
+        },
+        "bcs": "",
+        "timestampMs": "1718000120000"
+    }))
+    .unwrap();
+    event.bcs = bcs;
+    event
+}
+
+fn swap() -> MoveSwapEvent {
+    MoveSwapEvent {
+        account: SuiAddress::random_for_testing_only(),
+        pool_id: ObjectID::random(),
+        meme_in_amount: 0,
+        meme_out_amount: 5_000,
+        sui_in_amount: 100,
+        sui_out_amount: 0,
+        reserve_meme: 995_000,
+        reserve_sui: 10_100,
+    }
+}
+
+#[test]
+fn decodes_swap_event_matching_parsed_json() {
+    let swap = swap();
+    let bcs = bcs::to_bytes(&(
+        swap.account,
+        swap.pool_id,
+        swap.meme_in_amount,
+        swap.meme_out_amount,
+        swap.sui_in_amount,
+        swap.sui_out_amount,
+        swap.reserve_meme,
+        swap.reserve_sui,
+    ))
+    .unwrap();
+    assert_eq!(
+        decode::<MoveSwapEvent>(&swap_event(&swap, bcs)).unwrap(),
+        swap
+    );
+}
+
+#[test]
+fn uses_parsed_json_when_bcs_layout_differs() {
+    let swap = swap();
+    let mismatches = || {
+        metrics::EVENT_LAYOUT_MISMATCHES
+            .with_label_values(&["SwapEvent"])
+            .get()
+    };
+    let before = mismatches();
+    // 입출금 필드 순서가 Move와 다른 구조체로 디코딩한 것과 같습니다.
+    let bcs = bcs::to_bytes(&(
+        swap.account,
+        swap.pool_id,
+        swap.sui_in_amount,
+        swap.sui_out_amount,
+        swap.meme_in_amount,
+        swap.meme_out_amount,
+        swap.reserve_meme,
+        swap.reserve_sui,
+    ))
+    .unwrap();
+    assert_eq!(
+        decode::<MoveSwapEvent>(&swap_event(&swap, bcs)).unwrap(),
+        swap
+    );
+    assert_eq!(mismatches(), before + 1);
+
+    // BCS를 디코딩할 수 없어도 parsedJson으로 읽습니다.
+    assert_eq!(
+        decode::<MoveSwapEvent>(&swap_event(&swap, vec![1, 2, 3])).unwrap(),
+        swap
+    );
+}
+
+#[test]
+fn rejects_other_event_types() {
+    let swap = swap();
+    let mut event = swap_event(&swap, vec![]);
+    event.type_.name = Identifier::new("CreatePoolEvent").unwrap();
+
+    let error = decode::<MoveSwapEvent>(&event).unwrap_err();
+    assert_eq!(error.kind(), "parse");
+    assert!(
+        error.to_string().contains("unexpected event type"),
+        "{}",
+        error
+    );
+    assert!(decode::<MoveCreatePoolEvent>(&swap_event(&swap, vec![])).is_err());
+}
+
+fn assert_liquidity(event: &SuiEvent, liquidity: &MoveLiquidityEvent) {
+    assert_eq!(liquidity.account.to_string(), json_str(event, "/account"));
+    assert_eq!(liquidity.pool_id.to_string(), json_str(event, "/pool_id"));
+    assert_eq!(liquidity.meme_amount, json_u64(event, "/meme_amount"));
+    assert_eq!(liquidity.sui_amount, json_u64(event, "/sui_amount"));
+    assert_eq!(liquidity.lp_amount, json_u64(event, "/lp_amount"));
+    assert_eq!(liquidity.reserve_meme, json_u64(event, "/reserve_meme"));
+    assert_eq!(liquidity.reserve_sui, json_u64(event, "/reserve_sui"));
+}
+
+#[test]
+fn decodes_add_liquidity_event() {
+    let event = fixture("amm/add_liquidity_event");
+    let liquidity = decode::<MoveAddLiquidityEvent>(&event).unwrap().0;
+    assert_liquidity(&event, &liquidity);
+}
+
+#[test]
+fn unverified_liquidity_layout_needs_parsed_json() {
+    let mut event = fixture("amm/add_liquidity_event");
+    event.parsed_json = json!({ "amount": "1" });
+    let error = decode::<MoveAddLiquidityEvent>(&event).unwrap_err();
+    assert_eq!(error.kind(), "parse");
+}
+
+#[test]
+fn decodes_remove_liquidity_event() {
+    let event = fixture("amm/remove_liquidity_event");
+    let liquidity = decode::<MoveRemoveLiquidityEvent>(&event).unwrap().0;
+    assert_liquidity(&event, &liquidity);
+}
+
+#[test]
+fn decodes_graduate_event() {
+    let event = fixture("amm/graduate_event");
+    let graduate = decode::<MoveGraduateEvent>(&event).unwrap();
+    assert_eq!(graduate.pool_id.to_string(), json_str(&event, "/pool_id"));
+}
+
+#[test]
+fn unverified_lifecycle_layout_needs_parsed_json() {
+    let mut event = fixture("amm/migrate_pool_event");
+    event.parsed_json = json!({ "pool": "0x1" });
+    assert!(decode::<MoveMigratePoolEvent>(&event).is_err());
+}
+
+#[test]
+fn decodes_migrate_pool_event() {
+    let event = fixture("amm/migrate_pool_event");
+    let migrate = decode::<MoveMigratePoolEvent>(&event).unwrap();
+    assert_eq!(migrate.pool_id.to_string(), json_str(&event, "/pool_id"));
+    assert_eq!(migrate.venue, json_str(&event, "/venue"));
+    assert_eq!(
+        migrate.target_pool_id.to_string(),
+        json_str(&event, "/target_pool_id")
+    );
+}
@@ -0,0 +1,154 @@
+//! 이벤트 타입과 발생시킨 패키지, 패키지별 `events` 설정으로 처리기를 찾는지 확인합니다.
+
+use std::{str::FromStr, sync::Arc};
+
+use async_trait::async_trait;
+use gmi_server::{
+    config::{AmmPackage, DexConfig, DexVenue, PackageConfig},
+    dex::Venue,
+    error::Error,
+    handler::{EventHandler, HandlerContext, HandlerRegistry},
+};
+use serde_json::json;
+use sui_sdk::{
+    rpc_types::SuiEvent,
+    types::{base_types::ObjectID, digests::TransactionDigest, parse_sui_struct_tag, Identifier},
+};
+
+const ORIGINAL_PACKAGE: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+const UPGRADED_PACKAGE: &str = "0x2222222222222222222222222222222222222222222222222222222222222222";
+const OTHER_PACKAGE: &str = "0x3333333333333333333333333333333333333333333333333333333333333333";
+
+struct NoopHandler;
+
+#[async_trait]
+impl EventHandler for NoopHandler {
+    async fn handle(&self, _ctx: &HandlerContext<'_>, _event: SuiEvent) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+fn event(emitted_by: &str, event_type: &str) -> SuiEvent {
+    serde_json::from_value(json!({
+        "id": {
+            "txDigest": TransactionDigest::new([1; 32]).to_string(),
+            "eventSeq": "0"
+        },
+        "packageId": emitted_by,
+        "transactionModule": "amm",
+        "sender": ORIGINAL_PACKAGE,
+        "type": event_type,
+        "parsedJson": {},
+        "bcs": "",
+        "timestampMs": "1718000000000"
+    }))
+    .unwrap()
+}
+
+fn amm_event(emitted_by: &str, name: &str) -> SuiEvent {
+    event(emitted_by, &format!("{}::amm::{}", ORIGINAL_PACKAGE, name))
+}
+
+/// 원래 패키지는 풀 생성과 스왑을, 업그레이드한 패키지는 스왑과 유동성 공급을 처리합니다.
+fn packages() -> PackageConfig {
+    PackageConfig {
+        amm_packages: vec![
+            AmmPackage {
+                package_id: ObjectID::from_str(ORIGINAL_PACKAGE).unwrap(),
+                version: 1,
+                events: vec!["CreatePoolEvent".to_string(), "SwapEvent".to_string()],
+            },
+            AmmPackage {
+                package_id: ObjectID::from_str(UPGRADED_PACKAGE).unwrap(),
+                version: 2,
+                events: vec!["SwapEvent".to_string(), "AddLiquidityEvent".to_string()],
+            },
+        ],
+        amm_module: Identifier::new("amm").unwrap(),
+    }
+}
+
+#[test]
+fn resolves_amm_events_by_emitting_package() {
+    let packages = packages();
+    let registry = HandlerRegistry::amm(&packages).unwrap();
+
+    // 업그레이드한 패키지의 이벤트 타입은 원래 패키지 주소를 유지합니다.
+    assert!(registry
+        .resolve(&amm_event(ORIGINAL_PACKAGE, "SwapEvent"))
+        .is_some());
+    assert!(registry
+        .resolve(&amm_event(UPGRADED_PACKAGE, "SwapEvent"))
+        .is_some());
+    assert!(registry
+        .resolve(&amm_event(ORIGINAL_PACKAGE, "CreatePoolEvent"))
+        .is_some());
+    assert!(registry
+        .resolve(&amm_event(UPGRADED_PACKAGE, "AddLiquidityEvent"))
+        .is_some());
+
+    // `events`에 없는 이벤트는 다른 버전이 처리하더라도 찾지 않습니다.
+    assert!(registry
+        .resolve(&amm_event(UPGRADED_PACKAGE, "CreatePoolEvent"))
+        .is_none());
+    assert!(registry
+        .resolve(&amm_event(ORIGINAL_PACKAGE, "AddLiquidityEvent"))
+        .is_none());
+    assert!(registry
+        .resolve(&amm_event(ORIGINAL_PACKAGE, "RemoveLiquidityEvent"))
+        .is_none());
+
+    // 이름이 같아도 다른 모듈이나 패키지의 이벤트는 처리하지 않습니다.
+    let other_module = event(
+        ORIGINAL_PACKAGE,
+        &format!("{}::router::SwapEvent", ORIGINAL_PACKAGE),
+    );
+    assert!(registry.resolve(&other_module).is_none());
+    let other_package = event(OTHER_PACKAGE, &format!("{}::amm::SwapEvent", OTHER_PACKAGE));
+    assert!(registry.resolve(&other_package).is_none());
+
+    let upgraded = packages
+        .find(&ObjectID::from_str(UPGRADED_PACKAGE).unwrap())
+        .unwrap();
+    assert_eq!(upgraded.version, 2);
+}
+
+#[test]
+fn resolves_dex_events_by_defining_package() {
+    let mut registry = HandlerRegistry::default();
+    registry
+        .register_dex(&DexConfig {
+            venues: vec![DexVenue {
+                venue: Venue::Cetus,
+                package_id: ObjectID::from_str(OTHER_PACKAGE).unwrap(),
+            }],
+        })
+        .unwrap();
+
+    // 업그레이드한 DEX 패키지가 발생시켜도 타입을 정의한 패키지로 찾습니다.
+    let swap = event(
+        UPGRADED_PACKAGE,
+        &format!("{}::pool::SwapEvent", OTHER_PACKAGE),
+    );
+    assert!(registry.resolve(&swap).is_some());
+    let other_event = event(
+        OTHER_PACKAGE,
+        &format!("{}::pool::AddLiquidityEvent", OTHER_PACKAGE),
+    );
+    assert!(registry.resolve(&other_event).is_none());
+}
+
+#[test]
+fn generic_events_fall_back_to_handler_without_type_params() {
+    let mut registry = HandlerRegistry::default();
+    let event_type = format!("{}::pool::SwapEvent", OTHER_PACKAGE);
+    registry.register(
+        parse_sui_struct_tag(&event_type).unwrap(),
+        Arc::new(NoopHandler),
+    );
+
+    let generic = parse_sui_struct_tag(&format!("{}<0x2::sui::SUI>", event_type)).unwrap();
+    assert!(registry.get(&generic).is_some());
+    let other = parse_sui_struct_tag(&format!("{}::pool::FlashSwapEvent", OTHER_PACKAGE)).unwrap();
+    assert!(registry.get(&other).is_none());
+}
@@ -0,0 +1,243 @@
+//! 늦게 처리되거나 다시 처리된 이벤트가 저장된 거래, 차트와 잔고를 뒤바꾸지 않는지 검증
+
+use gmi_server::db::{
+    model::{
+        BalanceChange, ChartData, CreatePoolEvent, Lifecycle, SwapEvent, Trade, TradeData,
+        TradeType,
+    },
+    Database,
+};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use sui_sdk::types::base_types::{ObjectID, SuiAddress};
+
+/// 5분 구간의 시작(밀리초)
+const BUCKET_MS: u64 = 5 * 60 * 1000;
+
+fn trade(transaction_hash: &str, event_seq: u64, timestamp: u64) -> Trade {
+    Trade {
+        account: "0x1".to_string(),
+        trade_type: TradeType::Buy,
+        sui_amount: 1,
+        timestamp,
+        transaction_hash: transaction_hash.to_string(),
+        event_seq,
+        package_version: Some(1),
+        account_meme_balance: None,
+        venue: None,
+    }
+}
+
+#[test]
+fn saves_each_trade_event_once_in_time_order() {
+    let mut trades = TradeData::new();
+    assert!(trades.add_trade(trade("a", 0, 100)));
+    assert!(trades.add_trade(trade("c", 0, 300)));
+    // 재시도로 늦게 처리된 이전 거래
+    assert!(trades.add_trade(trade("b", 0, 200)));
+    // 같은 트랜잭션의 다른 이벤트
+    assert!(trades.add_trade(trade("b", 1, 200)));
+    assert!(!trades.add_trade(trade("b", 0, 200)));
+
+    let order: Vec<_> = trades
+        .trades
+        .iter()
+        .map(|trade| (trade.transaction_hash.as_str(), trade.event_seq))
+        .collect();
+    assert_eq!(order, [("c", 0), ("b", 1), ("b", 0), ("a", 0)]);
+}
+
+#[test]
+fn late_trade_does_not_replace_close_price() {
+    let mut charts = ChartData { charts: vec![] };
+    let start = 10 * BUCKET_MS + 1;
+    charts.apply_trade(start + 2_000, Decimal::from(3)).unwrap();
+    let chart = charts.apply_trade(start + 1_000, Decimal::from(2)).unwrap();
+
+    assert_eq!(charts.charts.len(), 1);
+    assert_eq!(chart.open_price, "2");
+    assert_eq!(chart.close_price, "3");
+    assert_eq!(chart.low_price, "2");
+    assert_eq!(chart.high_price, "3");
+}
+
+#[test]
+fn late_trade_in_old_bucket_is_inserted_in_order() {
+    let mut charts = ChartData { charts: vec![] };
+    charts
+        .apply_trade(10 * BUCKET_MS + 1, Decimal::from(1))
+        .unwrap();
+    charts
+        .apply_trade(12 * BUCKET_MS + 1, Decimal::from(3))
+        .unwrap();
+    let chart = charts
+        .apply_trade(11 * BUCKET_MS + 1, Decimal::from(2))
+        .unwrap();
+
+    assert_eq!(chart.close_price, "2");
+    let closes: Vec<_> = charts
+        .charts
+        .iter()
+        .map(|chart| chart.close_price.as_str())
+        .collect();
+    assert_eq!(closes, ["3", "2", "1"]);
+    assert!(charts
+        .charts
+        .windows(2)
+        .all(|pair| pair[0].chart_timestamp > pair[1].chart_timestamp));
+}
+
+#[test]
+fn liquidity_change_in_old_bucket_uses_previous_close() {
+    let mut charts = ChartData { charts: vec![] };
+    charts
+        .apply_trade(10 * BUCKET_MS + 1, Decimal::from(1))
+        .unwrap();
+    charts
+        .apply_trade(12 * BUCKET_MS + 1, Decimal::from(3))
+        .unwrap();
+    let chart = charts
+        .mark_liquidity_change(11 * BUCKET_MS + 1, Decimal::from(9))
+        .unwrap();
+
+    assert_eq!(chart.close_price, "1");
+    assert_eq!(chart.liquidity_changes, 1);
+    assert_eq!(charts.charts[1].chart_timestamp, chart.chart_timestamp);
+
+    // 거래 없이 만든 캔들의 종가는 그 구간의 거래로 바뀝니다.
+    let chart = charts
+        .apply_trade(11 * BUCKET_MS + 1, Decimal::from(2))
+        .unwrap();
+    assert_eq!(chart.close_price, "2");
+    assert_eq!(chart.liquidity_changes, 1);
+}
+
+const COIN_TYPE: &str = "0x2::meme::MEME";
+
+fn create_pool(timestamp: u64) -> CreatePoolEvent {
+    CreatePoolEvent {
+        coin_type: Some(COIN_TYPE.to_string()),
+        metadata_id: ObjectID::ZERO,
+        pool_id: ObjectID::ZERO,
+        reserve_meme: 1_000,
+        reserve_sui: 10,
+        account: SuiAddress::ZERO,
+        treasury_id: ObjectID::ZERO,
+        timestamp: Some(timestamp),
+        digest: Some("create".to_string()),
+        package_version: Some(1),
+    }
+}
+
+#[tokio::test]
+async fn late_pool_creation_keeps_swap_reserves() {
+    let db = Database::in_memory().await.unwrap();
+    // 스왑이 먼저 처리되어 Pool을 만들었습니다.
+    db.update_pool_reserve(
+        COIN_TYPE.to_string(),
+        ObjectID::ZERO.to_string(),
+        900,
+        20,
+        200,
+        Some(1),
+    )
+    .await
+    .unwrap();
+
+    let pool = db.save_pool(create_pool(100)).await.unwrap();
+    assert_eq!((pool.reserve_meme, pool.reserve_sui), (900, 20));
+    assert_eq!(pool.reserves_updated_at, 200);
+    assert_eq!(pool.time_stamp, 100);
+    assert_eq!(pool.lifecycle, Lifecycle::Launching);
+
+    // 다시 처리해도 실패하지 않고 저장된 값을 남깁니다.
+    let pool = db.save_pool(create_pool(100)).await.unwrap();
+    assert_eq!((pool.reserve_meme, pool.reserve_sui), (900, 20));
+    let stored = db.get_pool_info(COIN_TYPE).await.unwrap().unwrap();
+    assert_eq!(stored.reserve_meme, 900);
+}
+
+fn balance_change(transaction_hash: &str, amount: i64, timestamp: u64) -> BalanceChange {
+    BalanceChange {
+        account: SuiAddress::ZERO.to_string(),
+        coin_type: COIN_TYPE.to_string(),
+        transaction_hash: transaction_hash.to_string(),
+        amount,
+        balance: 0,
+        timestamp,
+        version: timestamp,
+    }
+}
+
+fn swap(transaction_hash: &str, timestamp: u64, account_meme_balance: u64) -> SwapEvent {
+    SwapEvent {
+        account: SuiAddress::ZERO,
+        pool_id: ObjectID::ZERO,
+        meme_in_amount: 0,
+        meme_out_amount: 30,
+        sui_in_amount: 1,
+        sui_out_amount: 0,
+        reserve_meme: 1_000,
+        reserve_sui: 10,
+        timestamp: Some(timestamp),
+        coin_type: Some(COIN_TYPE.to_string()),
+        account_meme_balance: Some(account_meme_balance),
+        digest: Some(transaction_hash.to_string()),
+        event_seq: Some(0),
+        current_price: None,
+        package_version: Some(1),
+        venue: None,
+    }
+}
+
+#[tokio::test]
+async fn late_balance_change_shifts_later_balances() {
+    let db = Database::in_memory().await.unwrap();
+    let account = SuiAddress::ZERO.to_string();
+    // 처음 본 계정은 0에서 시작합니다.
+    let (a, _) = db
+        .insert_balance_change(balance_change("a", 100, 100))
+        .await
+        .unwrap();
+    assert_eq!(a.balance, 100);
+    let (c, _) = db
+        .insert_balance_change(balance_change("c", -30, 300))
+        .await
+        .unwrap();
+    assert_eq!(c.balance, 70);
+    db.save_trade_data(swap("c", 300, 70)).await.unwrap();
+
+    // 재시도로 늦게 처리된 사이의 트랜잭션
+    let (b, shifted) = db
+        .insert_balance_change(balance_change("b", 50, 200))
+        .await
+        .unwrap();
+    assert_eq!(b.balance, 150);
+    let shifted: HashMap<_, _> = shifted
+        .iter()
+        .map(|change| (change.transaction_hash.clone(), change.clamped_balance()))
+        .collect();
+    assert_eq!(shifted, HashMap::from([("c".to_string(), 120)]));
+
+    let stored = db
+        .get_balance_change("c", COIN_TYPE, &account)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.balance, 120);
+    let balance = db
+        .get_account_balance(COIN_TYPE, &account)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        (balance.balance, balance.transaction_hash.as_str()),
+        (120, "c")
+    );
+
+    db.update_trade_balances(COIN_TYPE, &account, &shifted)
+        .await
+        .unwrap();
+    let trades = db.get_trade_data(COIN_TYPE).await.unwrap().unwrap();
+    assert_eq!(trades.trades[0].account_meme_balance, Some(120));
+}
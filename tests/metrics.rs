@@ -0,0 +1,74 @@
+//! 메트릭이 기록 전부터 노출되고, RPC 실패가 집계되며 다시 시도할 오류로 분류되는지 확인합니다.
+
+mod common;
+
+use std::time::Duration;
+
+use common::mock_rpc::{MockNode, MockState};
+use gmi_server::{
+    config::{NetworkConfig, RpcEndpoint},
+    error::{Error, OrMissing},
+    metrics,
+    sui::SuiClientPool,
+};
+
+#[test]
+fn exposes_metrics_before_they_are_recorded() {
+    metrics::init();
+    let encoded = metrics::encode().unwrap();
+    for name in [
+        "gmi_dead_lettered_events_total 0",
+        "gmi_negative_balances_total 0",
+        "gmi_rpc_failovers_total 0",
+        "gmi_chain_lag_ms 0",
+    ] {
+        assert!(encoded.contains(name), "{} missing in\n{}", name, encoded);
+    }
+}
+
+#[test]
+fn classifies_invalid_input_as_permanent() {
+    let parse = Error::parse("amount", "invalid digit");
+    assert_eq!(parse.kind(), "parse");
+    assert!(!parse.is_transient());
+    assert_eq!(parse.to_string(), "failed to parse amount: invalid digit");
+
+    let missing = None::<u64>.or_missing("timestamp_ms").unwrap_err();
+    assert_eq!(missing.kind(), "missing");
+    assert!(!missing.is_transient());
+}
+
+#[tokio::test]
+async fn failed_rpc_is_counted_and_transient() {
+    let node = MockNode::start(MockState::default());
+    let network = NetworkConfig {
+        build: "custom".to_string(),
+        endpoints: vec![RpcEndpoint {
+            http_url: node.http_url.clone(),
+            ws_url: node.ws_url.clone(),
+            basic_auth: None,
+            headers: vec![],
+        }],
+        ws_ping_interval: Duration::from_secs(30),
+        request_timeout: Duration::from_secs(5),
+        health_check_interval: Duration::from_secs(60),
+    };
+    let pool = SuiClientPool::new(&network).await.unwrap();
+
+    // mock 노드는 체크포인트 조회를 지원하지 않습니다.
+    let sui = pool.client();
+    let result = metrics::rpc(
+        "get_latest_checkpoint_sequence_number",
+        sui.read_api().get_latest_checkpoint_sequence_number(),
+    )
+    .await;
+    let error = Error::from(result.unwrap_err());
+    assert_eq!(error.kind(), "rpc");
+    assert!(error.is_transient());
+    assert_eq!(
+        metrics::RPC_ERRORS
+            .with_label_values(&["get_latest_checkpoint_sequence_number"])
+            .get(),
+        1
+    );
+}
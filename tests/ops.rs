@@ -0,0 +1,113 @@
+//! `/readyz`의 처리 지연 검사가 체인의 최신 AMM 이벤트와 비교되는지 확인합니다.
+
+mod common;
+
+use std::{net::TcpListener, str::FromStr, sync::Arc, time::Duration};
+
+use common::mock_rpc::{MockNode, MockState};
+use gmi_server::{
+    api::ops::{self, LagCheck, OpsState},
+    config::{NetworkConfig, RpcEndpoint},
+    db::Database,
+    metrics,
+    observe::EventSource,
+    sui::SuiClientPool,
+    supervisor::ComponentStates,
+};
+use serde_json::{json, Value};
+use sui_sdk::{
+    rpc_types::SuiEvent,
+    types::{base_types::ObjectID, digests::TransactionDigest, Identifier},
+};
+
+const PACKAGE: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+const STARTED_AT_MS: u64 = 1_718_000_000_000;
+const MAX_LAG: Duration = Duration::from_secs(300);
+
+fn amm_event(digest: u8, timestamp_ms: u64) -> SuiEvent {
+    serde_json::from_value(json!({
+        "id": {
+            "txDigest": TransactionDigest::new([digest; 32]).to_string(),
+            "eventSeq": "0"
+        },
+        "packageId": PACKAGE,
+        "transactionModule": "amm",
+        "sender": PACKAGE,
+        "type": format!("{}::amm::SwapEvent", PACKAGE),
+        "parsedJson": {},
+        "bcs": "",
+        "timestampMs": timestamp_ms.to_string()
+    }))
+    .unwrap()
+}
+
+/// 노드의 최신 AMM 이벤트가 `history`일 때 `/readyz`의 지연 검사 결과를 반환합니다.
+async fn lag_check(history: Vec<SuiEvent>) -> Value {
+    let node = MockNode::start(MockState {
+        history: history.into(),
+        modules: vec![("amm".to_string(), PACKAGE.to_string())].into(),
+        ..Default::default()
+    });
+    let network = NetworkConfig {
+        build: "custom".to_string(),
+        endpoints: vec![RpcEndpoint {
+            http_url: node.http_url.clone(),
+            ws_url: node.ws_url.clone(),
+            basic_auth: None,
+            headers: vec![],
+        }],
+        ws_ping_interval: Duration::from_secs(30),
+        request_timeout: Duration::from_secs(5),
+        health_check_interval: Duration::from_secs(60),
+    };
+    let source = EventSource::amm(
+        ObjectID::from_str(PACKAGE).unwrap(),
+        Identifier::new("amm").unwrap(),
+    );
+    let state = OpsState {
+        db: Arc::new(Database::in_memory().await.unwrap()),
+        sui: Some(Arc::new(SuiClientPool::new(&network).await.unwrap())),
+        components: ComponentStates::default(),
+        event_capacity: 10,
+        lag: Some(LagCheck::new(source, MAX_LAG, STARTED_AT_MS)),
+    };
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = axum::Server::from_tcp(listener)
+        .unwrap()
+        .serve(ops::router(state).into_make_service());
+    tokio::spawn(server);
+
+    let body: Value = reqwest::get(format!("http://{}/readyz", addr))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    body["checks"]["lag"].clone()
+}
+
+// 처리한 이벤트 시각은 프로세스 전역 메트릭이므로 한 테스트에서 차례로 확인합니다.
+#[tokio::test]
+async fn lag_is_measured_from_the_latest_event_on_chain() {
+    // 체인이 조용하면 오래된 이벤트가 최신이므로 지연으로 보지 않습니다.
+    let quiet = lag_check(vec![amm_event(1, STARTED_AT_MS - 3_600_000)]).await;
+    assert_eq!(quiet["ok"], true, "{}", quiet);
+    assert_eq!(quiet["detail"]["lagMs"], 0);
+
+    // 시작한 뒤 나온 이벤트를 처리하지 못하고 있습니다.
+    let latest = STARTED_AT_MS + 600_000;
+    let history = vec![
+        amm_event(1, STARTED_AT_MS - 3_600_000),
+        amm_event(2, latest),
+    ];
+    let behind = lag_check(history.clone()).await;
+    assert_eq!(behind["ok"], false, "{}", behind);
+    assert_eq!(behind["detail"]["lagMs"], 600_000);
+    assert_eq!(behind["detail"]["latestEventMs"], latest);
+
+    metrics::record_event_timestamp(latest - 60_000);
+    let caught_up = lag_check(history).await;
+    assert_eq!(caught_up["ok"], true, "{}", caught_up);
+    assert_eq!(caught_up["detail"]["lagMs"], 60_000);
+}
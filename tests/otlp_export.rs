@@ -0,0 +1,50 @@
+//! 로컬 OTLP 수집기로 span을 내보내는지 확인합니다.
+//!
+//! 수집기가 필요하므로 기본으로는 실행하지 않습니다. `otel/docker-compose.yml`로 수집기를 띄운 뒤
+//! `cargo test --test otlp_export -- --ignored`로 실행합니다.
+
+use std::sync::{Arc, Mutex};
+
+use gmi_server::{
+    config::{LogConfig, LogFormat, OtlpConfig},
+    telemetry,
+};
+use tracing::{info, info_span};
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires a local OTLP collector"]
+async fn exports_spans_to_local_collector() {
+    let endpoint = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+        .unwrap_or_else(|_| "http://localhost:4317".to_string());
+    // 내보내기 실패는 반환되지 않고 전역 오류 처리기로 전달됩니다.
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    opentelemetry::global::set_error_handler({
+        let errors = errors.clone();
+        move |e| errors.lock().unwrap().push(e.to_string())
+    })
+    .unwrap();
+
+    telemetry::init(
+        &LogConfig {
+            level: "info".to_string(),
+            format: LogFormat::Text,
+        },
+        Some(&OtlpConfig {
+            endpoint,
+            service_name: "gmi-server-test".to_string(),
+        }),
+    )
+    .unwrap();
+    {
+        let _event = info_span!("handle_event", event = "SwapEvent").entered();
+        let _rpc = info_span!("rpc", method = "get_balance").entered();
+        info!("exported to collector");
+    }
+    // 배치에 남은 span을 보내고 exporter를 닫습니다.
+    tokio::task::spawn_blocking(telemetry::shutdown)
+        .await
+        .unwrap();
+
+    let errors = errors.lock().unwrap();
+    assert!(errors.is_empty(), "export failed: {:?}", errors);
+}
@@ -0,0 +1,188 @@
+//! 처리 태스크가 이벤트마다 span을 열고, 처리할 수 없는 이벤트를 오류 종류에 따라 다루는지 확인합니다.
+
+mod common;
+
+use std::{
+    collections::HashMap,
+    fmt,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use common::mock_rpc::{MockNode, MockState};
+use gmi_server::{
+    config::{AmmPackage, NetworkConfig, PackageConfig, RetryConfig, RpcEndpoint},
+    db::Database,
+    handler::HandlerRegistry,
+    metrics,
+    observe::{
+        receive_event, AccountLocks, CoinTypeCache, EventChannel, EventPipeline, MigrationTargets,
+    },
+    sui::SuiClientPool,
+};
+use serde_json::json;
+use sui_sdk::{
+    rpc_types::SuiEvent,
+    types::{base_types::ObjectID, digests::TransactionDigest, Identifier},
+};
+use tokio::sync::{broadcast, mpsc};
+use tracing::{
+    field::{Field, Visit},
+    span, Subscriber,
+};
+use tracing_subscriber::{
+    layer::{Context, SubscriberExt},
+    registry::LookupSpan,
+    Layer, Registry,
+};
+
+const AMM_PACKAGE: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+
+/// 열린 span의 이름과 필드
+#[derive(Clone, Default)]
+struct SpanRecorder(Arc<Mutex<Vec<(String, HashMap<String, String>)>>>);
+
+impl SpanRecorder {
+    fn spans(&self, name: &str) -> Vec<HashMap<String, String>> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(span, _)| span == name)
+            .map(|(_, fields)| fields.clone())
+            .collect()
+    }
+}
+
+struct FieldRecorder<'a>(&'a mut HashMap<String, String>);
+
+impl Visit for FieldRecorder<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{:?}", value));
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for SpanRecorder {
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, _id: &span::Id, _ctx: Context<'_, S>) {
+        let mut fields = HashMap::new();
+        attrs.record(&mut FieldRecorder(&mut fields));
+        self.0
+            .lock()
+            .unwrap()
+            .push((attrs.metadata().name().to_string(), fields));
+    }
+}
+
+fn amm_event(name: &str, digest: u8) -> SuiEvent {
+    serde_json::from_value(json!({
+        "id": {
+            "txDigest": TransactionDigest::new([digest; 32]).to_string(),
+            "eventSeq": "0"
+        },
+        "packageId": AMM_PACKAGE,
+        "transactionModule": "amm",
+        "sender": AMM_PACKAGE,
+        "type": format!("{}::amm::{}", AMM_PACKAGE, name),
+        "parsedJson": {},
+        "bcs": "",
+        "timestampMs": (1_718_000_000_000u64 + digest as u64).to_string()
+    }))
+    .unwrap()
+}
+
+async fn pipeline(node: &MockNode, db: Arc<Database>) -> EventPipeline {
+    let network = NetworkConfig {
+        build: "custom".to_string(),
+        endpoints: vec![RpcEndpoint {
+            http_url: node.http_url.clone(),
+            ws_url: node.ws_url.clone(),
+            basic_auth: None,
+            headers: vec![],
+        }],
+        ws_ping_interval: Duration::from_secs(30),
+        request_timeout: Duration::from_secs(5),
+        health_check_interval: Duration::from_secs(60),
+    };
+    let packages = PackageConfig {
+        amm_packages: vec![AmmPackage {
+            package_id: ObjectID::from_str(AMM_PACKAGE).unwrap(),
+            version: 3,
+            events: vec!["CreatePoolEvent".to_string(), "SwapEvent".to_string()],
+        }],
+        amm_module: Identifier::new("amm").unwrap(),
+    };
+    EventPipeline {
+        pool: Arc::new(SuiClientPool::new(&network).await.unwrap()),
+        db,
+        update_sender: broadcast::channel(16).0,
+        handlers: Arc::new(HandlerRegistry::amm(&packages).unwrap()),
+        packages: Arc::new(packages),
+        coin_types: Arc::new(CoinTypeCache::default()),
+        migration_targets: Arc::new(MigrationTargets::default()),
+        account_locks: Arc::new(AccountLocks::default()),
+        retry: RetryConfig {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+        },
+    }
+}
+
+#[tokio::test]
+async fn unreadable_event_is_dead_lettered_within_its_span() {
+    let recorder = SpanRecorder::default();
+    let _subscriber = tracing::subscriber::set_default(Registry::default().with(recorder.clone()));
+    let node = MockNode::start(MockState::default());
+    let db = Arc::new(Database::in_memory().await.unwrap());
+    let pipeline = pipeline(&node, db.clone()).await;
+
+    let (event_sender, event_receiver) = mpsc::channel(16);
+    // 레이아웃이 맞지 않는 스왑과, 이 패키지의 `events`에 없는 유동성 공급
+    let swap = amm_event("SwapEvent", 1);
+    event_sender.send(swap.clone()).await.unwrap();
+    event_sender
+        .send(amm_event("AddLiquidityEvent", 2))
+        .await
+        .unwrap();
+    drop(event_sender);
+    receive_event(
+        pipeline,
+        EventChannel::Amm,
+        Arc::new(tokio::sync::Mutex::new(event_receiver)),
+    )
+    .await
+    .unwrap();
+
+    // 해석하지 못한 이벤트는 다시 시도하지 않고 바로 dead letter로 보냅니다.
+    let dead_letters = db.get_dead_letters().await.unwrap();
+    assert_eq!(dead_letters.len(), 1);
+    assert_eq!(dead_letters[0].tx_digest, swap.id.tx_digest.to_string());
+    assert_eq!(dead_letters[0].attempts, 1);
+    assert_eq!(dead_letters[0].package_version, 3);
+    assert!(db.get_pending_retries().await.unwrap().is_empty());
+    let cursor = db.get_event_cursor(AMM_PACKAGE).await.unwrap().unwrap();
+    assert_eq!(cursor.tx_digest, swap.id.tx_digest.to_string());
+
+    let spans = recorder.spans("event");
+    assert_eq!(spans.len(), 1);
+    assert_eq!(spans[0]["tx_digest"], swap.id.tx_digest.to_string());
+    assert_eq!(spans[0]["event_seq"], "0");
+    assert_eq!(spans[0]["event_type"], "SwapEvent");
+    assert_eq!(spans[0]["package_version"], "3");
+
+    assert_eq!(
+        metrics::EVENTS_PROCESSED
+            .with_label_values(&["SwapEvent", "parse"])
+            .get(),
+        1
+    );
+    assert_eq!(
+        metrics::UNHANDLED_AMM_EVENTS
+            .with_label_values(&[AMM_PACKAGE, "AddLiquidityEvent"])
+            .get(),
+        1
+    );
+    assert_eq!(metrics::DEAD_LETTERED_EVENTS.get(), 1);
+}
@@ -0,0 +1,58 @@
+//! 설정한 헤더가 로컬 프록시를 거쳐 노드에 HTTP 요청과 WebSocket 연결 모두에 전달되는지 확인합니다.
+
+mod common;
+
+use std::time::Duration;
+
+use common::mock_rpc::{MockNode, MockState};
+use gmi_server::{
+    config::{NetworkConfig, RpcEndpoint},
+    rpc_proxy::parse_header,
+    sui::SuiClientPool,
+};
+use sui_sdk::{
+    rpc_types::EventFilter,
+    types::{base_types::ObjectID, Identifier},
+};
+
+#[tokio::test]
+async fn sends_configured_headers_to_the_node() {
+    let node = MockNode::start(MockState::default());
+    let network = NetworkConfig {
+        build: "custom".to_string(),
+        endpoints: vec![RpcEndpoint {
+            http_url: node.http_url.clone(),
+            ws_url: node.ws_url.clone(),
+            basic_auth: Some(("user".to_string(), "pass".to_string())),
+            headers: vec![parse_header("x-api-key", "secret").unwrap()],
+        }],
+        ws_ping_interval: Duration::from_secs(30),
+        request_timeout: Duration::from_secs(5),
+        health_check_interval: Duration::from_secs(60),
+    };
+    let pool = SuiClientPool::new(&network).await.unwrap();
+    let filter = EventFilter::MoveEventModule {
+        package: ObjectID::ZERO,
+        module: Identifier::new("amm").unwrap(),
+    };
+    let client = pool.client();
+    // HTTP 요청과 WebSocket 구독 응답이 프록시를 거쳐 돌아옵니다.
+    client
+        .event_api()
+        .query_events(filter.clone(), None, None, false)
+        .await
+        .unwrap();
+    client.event_api().subscribe_event(filter).await.unwrap();
+
+    let headers = node.state.headers.lock().unwrap();
+    assert!(headers.len() >= 2, "{:?}", headers);
+    for headers in headers.iter() {
+        assert_eq!(headers["x-api-key"], "secret");
+        // SDK가 붙인 basic auth도 그대로 전달합니다.
+        assert!(headers["authorization"]
+            .to_str()
+            .unwrap()
+            .starts_with("Basic "));
+    }
+    assert_eq!(node.state.requests("suix_subscribeEvent").len(), 1);
+}
@@ -0,0 +1,155 @@
+//! 로컬 mock JSON-RPC 노드로 구독, cursor 이후 재조회, 노드 전환을 확인합니다.
+
+mod common;
+
+use std::{str::FromStr, sync::Arc, time::Duration};
+
+use common::mock_rpc::{MockNode, MockState};
+use gmi_server::{
+    config::{NetworkConfig, RpcEndpoint},
+    observe::{follow_events, EventSource},
+    shutdown,
+    sui::SuiClientPool,
+};
+use serde_json::json;
+use sui_sdk::{
+    rpc_types::SuiEvent,
+    types::{base_types::ObjectID, digests::TransactionDigest, Identifier},
+};
+use tokio::sync::mpsc;
+
+const ORIGINAL_PACKAGE: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+const UPGRADED_PACKAGE: &str = "0x2222222222222222222222222222222222222222222222222222222222222222";
+const RECV_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `emitted_by` 패키지가 발생시킨, 원래 패키지에 정의된 AMM 이벤트
+fn amm_event(emitted_by: &str, digest: u8) -> SuiEvent {
+    serde_json::from_value(json!({
+        "id": {
+            "txDigest": TransactionDigest::new([digest; 32]).to_string(),
+            "eventSeq": "0"
+        },
+        "packageId": emitted_by,
+        "transactionModule": "amm",
+        "sender": ORIGINAL_PACKAGE,
+        "type": format!("{}::amm::SwapEvent", ORIGINAL_PACKAGE),
+        "parsedJson": {},
+        "bcs": "",
+        "timestampMs": (1_718_000_000_000u64 + digest as u64).to_string()
+    }))
+    .unwrap()
+}
+
+fn network(nodes: &[&MockNode]) -> NetworkConfig {
+    NetworkConfig {
+        build: "custom".to_string(),
+        endpoints: nodes
+            .iter()
+            .map(|node| RpcEndpoint {
+                http_url: node.http_url.clone(),
+                ws_url: node.ws_url.clone(),
+                basic_auth: None,
+                headers: vec![],
+            })
+            .collect(),
+        ws_ping_interval: Duration::from_secs(30),
+        request_timeout: Duration::from_secs(5),
+        health_check_interval: Duration::from_secs(60),
+    }
+}
+
+fn amm_source() -> EventSource {
+    EventSource::amm(
+        ObjectID::from_str(UPGRADED_PACKAGE).unwrap(),
+        Identifier::new("amm").unwrap(),
+    )
+}
+
+async fn recv(receiver: &mut mpsc::Receiver<SuiEvent>) -> SuiEvent {
+    tokio::time::timeout(RECV_TIMEOUT, receiver.recv())
+        .await
+        .expect("timed out waiting for an event")
+        .expect("event channel closed")
+}
+
+#[tokio::test]
+async fn replays_missed_events_by_event_module() {
+    let cursor = amm_event(UPGRADED_PACKAGE, 1);
+    let missed = amm_event(UPGRADED_PACKAGE, 2);
+    let other_version = amm_event(ORIGINAL_PACKAGE, 3);
+    let missed_and_live = amm_event(UPGRADED_PACKAGE, 4);
+    let live = amm_event(UPGRADED_PACKAGE, 5);
+    let node = MockNode::start(MockState {
+        history: vec![
+            cursor.clone(),
+            missed.clone(),
+            other_version,
+            missed_and_live.clone(),
+        ]
+        .into(),
+        live: vec![missed_and_live.clone(), live.clone()].into(),
+        modules: vec![("amm".to_string(), ORIGINAL_PACKAGE.to_string())].into(),
+        ..Default::default()
+    });
+    let pool = Arc::new(SuiClientPool::new(&network(&[&node])).await.unwrap());
+    let (sender, mut receiver) = mpsc::channel(16);
+    let (shutdown_sender, shutdown) = shutdown::channel();
+    let task = tokio::spawn(follow_events(
+        pool,
+        amm_source(),
+        Some(cursor.id.clone()),
+        sender,
+        shutdown,
+    ));
+
+    // 다른 버전이 발생시킨 이벤트는 건너뛰고, 재조회와 구독에 모두 온 이벤트는 한 번만 보냅니다.
+    assert_eq!(recv(&mut receiver).await.id, missed.id);
+    assert_eq!(recv(&mut receiver).await.id, missed_and_live.id);
+    assert_eq!(recv(&mut receiver).await.id, live.id);
+
+    let queries = node.state.requests("suix_queryEvents");
+    assert!(!queries.is_empty());
+    for params in queries {
+        assert_eq!(
+            params[0],
+            json!({ "MoveEventModule": { "package": ORIGINAL_PACKAGE, "module": "amm" } })
+        );
+    }
+
+    shutdown_sender.send_replace(true);
+    task.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn fails_over_when_subscription_is_rejected() {
+    let event = amm_event(UPGRADED_PACKAGE, 9);
+    let broken = MockNode::start(MockState {
+        fail_subscribe: true.into(),
+        ..Default::default()
+    });
+    let healthy = MockNode::start(MockState {
+        live: vec![event.clone()].into(),
+        ..Default::default()
+    });
+    let pool = Arc::new(
+        SuiClientPool::new(&network(&[&broken, &healthy]))
+            .await
+            .unwrap(),
+    );
+    let (sender, mut receiver) = mpsc::channel(16);
+    let (shutdown_sender, shutdown) = shutdown::channel();
+    let task = tokio::spawn(follow_events(
+        pool.clone(),
+        amm_source(),
+        None,
+        sender,
+        shutdown,
+    ));
+
+    assert_eq!(recv(&mut receiver).await.id, event.id);
+    assert_eq!(pool.current_endpoint().http_url, healthy.http_url);
+    assert!(!broken.state.requests("suix_subscribeEvent").is_empty());
+
+    shutdown_sender.send_replace(true);
+    task.await.unwrap().unwrap();
+}
@@ -0,0 +1,95 @@
+//! 실패한 태스크를 재시작하고, 연속 실패가 한도를 넘거나 종료 요청이 오면 멈추는지 확인합니다.
+
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use anyhow::anyhow;
+use gmi_server::{
+    config::SupervisorConfig,
+    shutdown,
+    supervisor::{ComponentState, Supervisor},
+};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+fn config(max_restarts: u32) -> SupervisorConfig {
+    SupervisorConfig {
+        max_restarts,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(5),
+        stable_after: Duration::from_secs(3600),
+    }
+}
+
+#[tokio::test]
+async fn gives_up_after_max_restarts() {
+    let supervisor = Supervisor::new(config(2));
+    let states = supervisor.states();
+    let (_shutdown_sender, shutdown) = shutdown::channel();
+    let starts = Arc::new(AtomicU32::new(0));
+
+    let counter = starts.clone();
+    let result = tokio::time::timeout(
+        TIMEOUT,
+        supervisor.supervise("worker", shutdown, move || {
+            let attempt = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            async move { Err(anyhow!("boom {}", attempt)) }
+        }),
+    )
+    .await
+    .expect("supervisor did not give up");
+
+    assert!(result.is_err());
+    // 처음 실행과 재시작 두 번
+    assert_eq!(starts.load(Ordering::SeqCst), 3);
+    match &states.snapshot()["worker"] {
+        ComponentState::Failed { last_error } => assert!(last_error.contains("boom 3")),
+        state => panic!("unexpected state {:?}", state),
+    }
+}
+
+#[tokio::test]
+async fn restarts_panicked_task_until_shutdown() {
+    let supervisor = Supervisor::new(config(5));
+    let states = supervisor.states();
+    let (shutdown_sender, shutdown) = shutdown::channel();
+    let starts = Arc::new(AtomicU32::new(0));
+
+    let counter = starts.clone();
+    let task_shutdown = shutdown.clone();
+    let supervised = tokio::spawn(supervisor.supervise("worker", shutdown, move || {
+        let attempt = counter.fetch_add(1, Ordering::SeqCst) + 1;
+        let mut shutdown = task_shutdown.clone();
+        async move {
+            if attempt == 1 {
+                panic!("first start panics");
+            }
+            shutdown::requested(&mut shutdown).await;
+            Ok(())
+        }
+    }));
+
+    tokio::time::timeout(TIMEOUT, async {
+        while starts.load(Ordering::SeqCst) < 2
+            || states.snapshot().get("worker") != Some(&ComponentState::Running)
+        {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("task was not restarted");
+
+    shutdown_sender.send(true).unwrap();
+    let result = tokio::time::timeout(TIMEOUT, supervised)
+        .await
+        .expect("supervisor did not stop")
+        .unwrap();
+    assert!(result.is_ok());
+    assert_eq!(starts.load(Ordering::SeqCst), 2);
+    assert_eq!(states.snapshot()["worker"], ComponentState::Stopped);
+}